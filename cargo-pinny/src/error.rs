@@ -0,0 +1,43 @@
+use pinny_core::config::ConfigError;
+use pinny_core::expression::ExpressionError;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to run `{command}`: {source}")]
+    Spawn {
+        command: String,
+        source: std::io::Error,
+    },
+    #[error("`{command}` failed ({status})")]
+    Command { command: String, status: ExitStatus },
+    #[error("Invalid nextest test list: {0}")]
+    InvalidNextestList(String),
+    #[error(transparent)]
+    Expression(#[from] ExpressionError),
+    #[error("No test matches `{0}`")]
+    NoMatchingTest(String),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Failed to access {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid nextest config {}: {1}", .0.display())]
+    InvalidNextestConfig(PathBuf, toml_edit::TomlError),
+    #[error("{} is out of date, run `cargo pinny nextest-config` to refresh it", .0.display())]
+    OutOfDate(PathBuf),
+    #[error("Failed to parse {}: {1}", .0.display())]
+    InvalidSource(PathBuf, String),
+    #[error("{0} allowed tag(s) never used")]
+    UnusedTags(usize),
+    #[error("{0} test(s) carrying no tag")]
+    UntaggedTests(usize),
+    #[error("Invalid JUnit report {}: {1}", .0.display())]
+    InvalidJunit(PathBuf, String),
+    #[error("Invalid test results {}: {1}", .0.display())]
+    InvalidTestResults(PathBuf, String),
+}
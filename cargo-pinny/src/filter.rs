@@ -0,0 +1,54 @@
+//! `cargo pinny filter`: compile a tag expression to the filters of the test runners.
+use crate::{error::Error, listing};
+use pinny_core::config::ConfigFactory;
+use pinny_core::expression::Expr;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Tag expression, e.g. `bitcoin and not (slow or flaky)`
+    pub expression: String,
+    /// Print the names of the matching tests, as exact libtest filters
+    /// (e.g. `cargo test -- --exact $(cargo pinny filter --libtest <expr>)`),
+    /// instead of a nextest filterset
+    #[arg(long)]
+    pub libtest: bool,
+    /// Arguments passed to cargo to list the tests (e.g. `-p my_crate`)
+    #[arg(last = true, value_name = "CARGO_ARGS")]
+    pub cargo: Vec<String>,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let expr = resolve(Expr::parse(&args.expression)?)?;
+
+    if args.libtest {
+        let options = listing::Options {
+            nextest: false,
+            cargo_args: args.cargo.clone(),
+        };
+        let tests = listing::tests(&options)?;
+        let names =
+            expr.libtest_filters(tests.iter().map(|test| test.path.as_str()));
+        if names.is_empty() {
+            // no filter at all would run every test
+            return Err(Error::NoMatchingTest(expr.to_string()));
+        }
+        println!("{}", names.join("\n"));
+    } else {
+        println!("{}", expr.to_nextest());
+    }
+    Ok(())
+}
+
+/// Resolve the tags of the expression with the configuration of the package
+/// in the current directory, if any (e.g. not at the root of a workspace).
+fn resolve(expr: Expr) -> Result<Expr, Error> {
+    match ConfigFactory::from_manifest_dir(".") {
+        Ok(config) => Ok(expr.resolve(&config)?),
+        Err(error) => {
+            eprintln!(
+                "warning: tags not checked against the pinny config: {error}"
+            );
+            Ok(expr)
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! `cargo pinny junit`: JUnit reports with the original test names and the tags.
+//!
+//! Both the nextest and the libtest (`--format junit`) reports are supported:
+//! - nextest reports the whole test name (e.g. `tests::test_hello::t::slow::t`)
+//!   as the `name` of each `<testcase>`, which becomes `tests::test_hello`
+//! - libtest splits it in `classname` (module path) and `name` (last segment),
+//!   which become `tests` and `test_hello`
+//!
+//! The tags are reported as `<property name="tag" value="slow"/>` of the test
+//! case, or as a prefix of its `classname` (e.g. `[slow, bitcoin] my-crate`).
+use crate::error::Error;
+use pinny_core::mangling::{self, TaggedTest};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use std::path::{Path, PathBuf};
+
+/// Name of the properties reporting the tags of a test case
+pub const TAG_PROPERTY: &str = "tag";
+
+/// Where the tags of the test cases are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TagsIn {
+    /// `<properties>` of the test case, one `tag` property per tag
+    #[default]
+    Properties,
+    /// Prefix of the `classname` of the test case
+    Classname,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// JUnit report to enrich (e.g. `target/nextest/default/junit.xml`)
+    pub input: PathBuf,
+    /// Output file, the standard output by default (can be the input itself)
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Where to report the tags of the tests
+    #[arg(long, value_enum, default_value_t)]
+    pub tags_in: TagsIn,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let report = read(&args.input)?;
+    let enriched = enrich(&report, args.tags_in)
+        .map_err(|error| Error::InvalidJunit(args.input.clone(), error))?;
+    if let Some(output) = &args.output {
+        std::fs::write(output, enriched).map_err(|source| Error::Io {
+            path: output.clone(),
+            source,
+        })
+    } else {
+        print!("{enriched}");
+        Ok(())
+    }
+}
+
+/// Restore the original names of the tagged test cases of a JUnit report,
+/// adding their tags. Any other content of the report is left untouched.
+pub fn enrich(report: &str, tags_in: TagsIn) -> Result<String, String> {
+    let mut reader = Reader::from_str(report);
+    let mut writer = Writer::new(Vec::new());
+    loop {
+        let event = reader.read_event().map_err(|error| error.to_string())?;
+        match event {
+            Event::Eof => break,
+            Event::Start(start) if start.name().as_ref() == b"testcase" => {
+                let (start, tags) = restore(&start, tags_in)?;
+                write(&mut writer, Event::Start(start))?;
+                write_properties(&mut writer, &tags)?;
+            }
+            Event::Empty(start) if start.name().as_ref() == b"testcase" => {
+                let (start, tags) = restore(&start, tags_in)?;
+                if tags.is_empty() {
+                    write(&mut writer, Event::Empty(start))?;
+                } else {
+                    let end = start.to_end().into_owned();
+                    write(&mut writer, Event::Start(start))?;
+                    write_properties(&mut writer, &tags)?;
+                    write(&mut writer, Event::End(end))?;
+                }
+            }
+            event => write(&mut writer, event)?,
+        }
+    }
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+/// Restore the `name` and `classname` of a tagged test case, returning the
+/// tags to be reported as properties (none when reported in the classname).
+fn restore(
+    testcase: &BytesStart,
+    tags_in: TagsIn,
+) -> Result<(BytesStart<'static>, Vec<String>), String> {
+    let attributes = attributes(testcase)?;
+    let attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(each, _)| each == key)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let name = attribute("name").unwrap_or_default();
+    let classname = attribute("classname");
+    let Some((test, naming)) = decode_testcase(name, classname) else {
+        return Ok((testcase.clone().into_owned(), Vec::new()));
+    };
+    let (name, classname) = match naming {
+        Naming::Whole => (test.path(), classname.map(ToString::to_string)),
+        Naming::Split => {
+            let classname = match test.module_path.as_str() {
+                "" => "crate".to_string(),
+                module_path => module_path.to_string(),
+            };
+            (test.name, Some(classname))
+        }
+    };
+    let tags = test.tags;
+
+    let (classname, properties) = match tags_in {
+        TagsIn::Properties => (classname, tags),
+        TagsIn::Classname => {
+            let prefix = format!("[{}]", tags.join(", "));
+            let classname = match classname {
+                Some(classname) => format!("{prefix} {classname}"),
+                None => prefix,
+            };
+            (Some(classname), Vec::new())
+        }
+    };
+
+    // same attributes, in the same order
+    let mut restored = BytesStart::new("testcase");
+    for (key, value) in &attributes {
+        let value = match key.as_str() {
+            "name" => &name,
+            "classname" => classname.as_ref().unwrap_or(value),
+            _ => value,
+        };
+        restored.push_attribute((key.as_str(), value.as_str()));
+    }
+    if let (None, Some(classname)) = (attribute("classname"), &classname) {
+        restored.push_attribute(("classname", classname.as_str()));
+    }
+    Ok((restored, properties))
+}
+
+/// Attributes (key and unescaped value) of an element, in order
+pub fn attributes(
+    element: &BytesStart,
+) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    for attr in element.attributes() {
+        let attr = attr.map_err(|error| error.to_string())?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+        let value = attr.unescape_value().map_err(|error| error.to_string())?;
+        attributes.push((key, value.into_owned()));
+    }
+    Ok(attributes)
+}
+
+/// How the test name is reported by a test case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Naming {
+    /// Whole test name as `name` (nextest, libtest integration tests)
+    Whole,
+    /// Module path as `classname` and function as `name` (libtest unit tests)
+    Split,
+}
+
+/// Decode a tagged test case from its `name` and `classname` attributes
+pub fn decode_testcase(
+    name: &str,
+    classname: Option<&str>,
+) -> Option<(TaggedTest, Naming)> {
+    if let Some(test) = mangling::decode(name) {
+        return Some((test, Naming::Whole));
+    }
+    let test = mangling::decode(&format!("{}::{name}", classname?))?;
+    Some((test, Naming::Split))
+}
+
+/// Split a classname prefixed with the tags of its test case
+/// (e.g. `[slow, bitcoin] my-crate`) into the tags and the classname
+pub fn split_classname(classname: &str) -> Option<(Vec<String>, &str)> {
+    let (tags, classname) = classname.strip_prefix('[')?.split_once(']')?;
+    let tags = tags.split(", ").map(ToString::to_string).collect();
+    Some((tags, classname.strip_prefix(' ').unwrap_or(classname)))
+}
+
+fn write_properties(
+    writer: &mut Writer<Vec<u8>>,
+    tags: &[String],
+) -> Result<(), String> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    write(writer, Event::Start(BytesStart::new("properties")))?;
+    for tag in tags {
+        let mut property = BytesStart::new("property");
+        property.push_attribute(("name", TAG_PROPERTY));
+        property.push_attribute(("value", tag.as_str()));
+        write(writer, Event::Empty(property))?;
+    }
+    write(writer, Event::End(BytesEnd::new("properties")))
+}
+
+fn write(writer: &mut Writer<Vec<u8>>, event: Event) -> Result<(), String> {
+    writer.write_event(event).map_err(|error| error.to_string())
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
@@ -0,0 +1,77 @@
+//! `cargo pinny list`: inventory of the tagged tests.
+use crate::{error::Error, listing};
+use pinny_core::config::{Config, ConfigFactory};
+use pinny_core::mangling::TaggedTest;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Group the tests by tag, with the number of tests of each tag
+    #[arg(long)]
+    pub by_tag: bool,
+    /// Show the description of each tag, from the pinny config
+    #[arg(long, requires = "by_tag")]
+    pub describe: bool,
+    /// Directory of the package declaring the tags (with `--describe`)
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub manifest_dir: PathBuf,
+    #[command(flatten)]
+    pub listing: listing::Options,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let config = if args.describe {
+        Some(ConfigFactory::from_manifest_dir(&args.manifest_dir)?)
+    } else {
+        None
+    };
+    let tests = listing::tagged_tests(&args.listing)?;
+    if args.by_tag {
+        print!("{}", render_by_tag(&tests, config.as_ref()));
+    } else {
+        print!("{}", render(&tests));
+    }
+    Ok(())
+}
+
+/// Render each test with its tags, e.g. `tests::test_hello: tag1, tag2`
+pub fn render(tests: &[TaggedTest]) -> String {
+    tests.iter().fold(String::new(), |mut output, test| {
+        let _ = writeln!(output, "{}: {}", test.path(), test.tags.join(", "));
+        output
+    })
+}
+
+/// Render the tests grouped by tag (sorted by name), with the number of tests
+/// of each tag and its description when given the config, e.g.
+///
+/// ```text
+/// tag1: 2 tests - Fast unit tests
+///     tests::test_hello
+///     tests::test_world
+/// ```
+pub fn render_by_tag(tests: &[TaggedTest], config: Option<&Config>) -> String {
+    let mut by_tag: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for test in tests {
+        for tag in &test.tags {
+            by_tag.entry(tag).or_default().push(test.path());
+        }
+    }
+
+    let mut output = String::new();
+    for (tag, paths) in by_tag {
+        let count = paths.len();
+        let noun = if count == 1 { "test" } else { "tests" };
+        let _ = write!(output, "{tag}: {count} {noun}");
+        if let Some(description) = config.and_then(|c| c.description(tag)) {
+            let _ = write!(output, " - {description}");
+        }
+        output.push('\n');
+        for path in paths {
+            let _ = writeln!(output, "    {path}");
+        }
+    }
+    output
+}
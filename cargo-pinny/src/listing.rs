@@ -0,0 +1,123 @@
+//! Listing of the tests, as reported by the test harness (`cargo test`) or by nextest.
+use crate::error::Error;
+use pinny_core::mangling::{self, TaggedTest};
+use std::process::{Command, Stdio};
+
+/// Options to list the tests, shared by the subcommands
+#[derive(Debug, Default, clap::Args)]
+pub struct Options {
+    /// List the tests with nextest (`cargo nextest list`) instead of `cargo test`
+    #[arg(long)]
+    pub nextest: bool,
+    /// Arguments passed to cargo (e.g. `-p my_crate --all-features`)
+    #[arg(last = true, value_name = "CARGO_ARGS")]
+    pub cargo_args: Vec<String>,
+}
+
+/// A test, as listed by the test harness
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Test {
+    /// Path of the test, as reported by the test harness (i.e. mangled, when tagged)
+    pub path: String,
+    /// Decoded test, `None` when the test is not tagged
+    pub tagged: Option<TaggedTest>,
+}
+
+impl Test {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            tagged: mangling::decode(path),
+        }
+    }
+}
+
+/// List the tests, building the test binaries if needed
+pub fn tests(options: &Options) -> Result<Vec<Test>, Error> {
+    let paths = if options.nextest {
+        let output = cargo(
+            ["nextest", "list", "--message-format", "json"],
+            &options.cargo_args,
+            [],
+        )?;
+        parse_nextest(&output)?
+    } else {
+        let output = cargo(
+            ["test"],
+            &options.cargo_args,
+            ["--", "--list", "--format", "terse"],
+        )?;
+        parse_terse(&output)
+    };
+    Ok(paths.iter().map(|path| Test::new(path)).collect())
+}
+
+/// List the tagged tests only
+pub fn tagged_tests(options: &Options) -> Result<Vec<TaggedTest>, Error> {
+    Ok(tests(options)?
+        .into_iter()
+        .filter_map(|test| test.tagged)
+        .collect())
+}
+
+/// Parse the test paths from the output of `cargo test -- --list --format terse`,
+/// that is lines as `tests::test_hello: test` (benchmarks are skipped)
+pub fn parse_terse(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Parse the test paths from the output of `cargo nextest list --message-format json`
+pub fn parse_nextest(output: &str) -> Result<Vec<String>, Error> {
+    let list: serde_json::Value = serde_json::from_str(output)
+        .map_err(|error| Error::InvalidNextestList(error.to_string()))?;
+    let suites = list["rust-suites"].as_object().ok_or_else(|| {
+        Error::InvalidNextestList("missing 'rust-suites'".into())
+    })?;
+
+    let mut paths = Vec::new();
+    for (binary_id, suite) in suites {
+        let testcases = suite["testcases"].as_object().ok_or_else(|| {
+            Error::InvalidNextestList(format!(
+                "missing 'testcases' of '{binary_id}'"
+            ))
+        })?;
+        paths.extend(testcases.keys().cloned());
+    }
+    Ok(paths)
+}
+
+/// Run a cargo command, returning its standard output
+///
+/// The standard error is inherited, so that the build progress is still shown.
+fn cargo<'a>(
+    command: impl IntoIterator<Item = &'a str>,
+    cargo_args: &'a [String],
+    trailing_args: impl IntoIterator<Item = &'a str>,
+) -> Result<String, Error> {
+    let args: Vec<&str> = command
+        .into_iter()
+        .chain(cargo_args.iter().map(String::as_str))
+        .chain(trailing_args)
+        .collect();
+    let command = format!("cargo {}", args.join(" "));
+
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let mut process = Command::new(cargo);
+    process.args(args).stderr(Stdio::inherit());
+
+    let output = process.output().map_err(|source| Error::Spawn {
+        command: command.clone(),
+        source,
+    })?;
+    if !output.status.success() {
+        return Err(Error::Command {
+            command,
+            status: output.status,
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
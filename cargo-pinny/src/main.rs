@@ -0,0 +1,83 @@
+//! # Cargo Pinny
+//!
+//! Cargo subcommand to work with the tests tagged by [pinny](https://crates.io/crates/pinny):
+//!
+//! ```sh
+//! cargo pinny list             # each tagged test with its tags
+//! cargo pinny list --by-tag    # tagged tests grouped by tag, with counts
+//! cargo pinny list --by-tag --describe  # with the tag descriptions
+//! cargo pinny filter 'bitcoin and not (slow or flaky)'  # nextest filterset
+//! cargo pinny nextest-config   # nextest profiles of the tags and tag groups
+//! cargo pinny unused --deny    # fail on allowed tags no test uses
+//! cargo pinny untagged --deny  # fail on tests carrying no tag
+//! cargo pinny junit target/nextest/default/junit.xml  # original test names, with tags
+//! cargo pinny report target/nextest/default/junit.xml # per-tag counts and durations
+//! ```
+mod error;
+mod filter;
+mod junit;
+mod list;
+mod listing;
+mod nextest_config;
+mod report;
+mod sources;
+#[cfg(test)]
+mod tests;
+mod untagged;
+mod unused;
+
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    Pinny(Pinny),
+}
+
+/// Work with the tests tagged by pinny
+#[derive(clap::Args)]
+#[command(version, about)]
+struct Pinny {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the tagged tests with their tags
+    List(list::Args),
+    /// Compile a tag expression to a nextest filterset or to libtest filters
+    Filter(filter::Args),
+    /// Generate or refresh the nextest profiles of the tags and tag groups
+    NextestConfig(nextest_config::Args),
+    /// List the allowed tags no test uses
+    Unused(unused::Args),
+    /// List the test functions carrying no tag
+    Untagged(untagged::Args),
+    /// Restore the original test names of a JUnit report, adding the tags
+    Junit(junit::Args),
+    /// Report the counts and durations of the tests of each tag
+    Report(report::Args),
+}
+
+fn main() -> ExitCode {
+    let Cargo::Pinny(pinny) = Cargo::parse();
+    let result = match pinny.command {
+        Command::List(args) => list::run(&args),
+        Command::Filter(args) => filter::run(&args),
+        Command::NextestConfig(args) => nextest_config::run(&args),
+        Command::Unused(args) => unused::run(&args),
+        Command::Untagged(args) => untagged::run(&args),
+        Command::Junit(args) => junit::run(&args),
+        Command::Report(args) => report::run(&args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
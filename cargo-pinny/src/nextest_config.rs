@@ -0,0 +1,144 @@
+//! `cargo pinny nextest-config`: nextest profiles generated from the pinny config.
+//!
+//! Each allowed tag gets a `pinny-<tag>` profile and each tag group a
+//! `pinny-group-<group>` profile, whose `default-filter` selects the tests
+//! having the tag (any tag of the group). The generated profiles are marked by
+//! a comment, any other section of the file (`pinny-*` profiles written by hand
+//! included) is left untouched.
+use crate::error::Error;
+use pinny_core::config::{Config, ConfigFactory};
+use pinny_core::expression::Expr;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, DocumentMut, Item, Table};
+
+/// Prefix of the profiles managed by the command
+pub const PROFILE_PREFIX: &str = "pinny-";
+
+/// Comment marking the profiles generated by the command
+pub const GENERATED_MARKER: &str =
+    "# Generated by `cargo pinny nextest-config`";
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Directory of the package holding the pinny config
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub manifest_dir: PathBuf,
+    /// Nextest config file to generate or refresh
+    #[arg(long, value_name = "FILE", default_value = ".config/nextest.toml")]
+    pub config_file: PathBuf,
+    /// Check that the profiles are up to date, without writing the file
+    #[arg(long)]
+    pub check: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let config = ConfigFactory::from_manifest_dir(&args.manifest_dir)?;
+    let path = &args.config_file;
+    let current = if path.exists() {
+        std::fs::read_to_string(path)
+            .map_err(|source| io_error(path, source))?
+    } else {
+        String::new()
+    };
+
+    let refreshed = refresh(&current, &config)
+        .map_err(|error| Error::InvalidNextestConfig(path.clone(), error))?;
+    if refreshed == current {
+        println!("{} is up to date", path.display());
+    } else if args.check {
+        return Err(Error::OutOfDate(path.clone()));
+    } else {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|source| io_error(parent, source))?;
+        }
+        std::fs::write(path, refreshed)
+            .map_err(|source| io_error(path, source))?;
+        println!("{} updated", path.display());
+    }
+    Ok(())
+}
+
+/// Refresh the pinny profiles of a nextest config, returning the new content.
+///
+/// The filters of the existing profiles are updated (other settings of the
+/// profiles are kept), the missing profiles are added and the generated
+/// profiles of the tags and groups no longer in the pinny config are removed.
+pub fn refresh(
+    content: &str,
+    config: &Config,
+) -> Result<String, toml_edit::TomlError> {
+    let mut document: DocumentMut = content.parse()?;
+    let profiles = pinny_profiles(config);
+
+    let root = document.as_table_mut();
+    if !root.contains_key("profile") {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        root.insert("profile", Item::Table(table));
+    }
+    let Some(table) = root["profile"].as_table_mut() else {
+        // not a table (e.g. an inline one): leave the user setting untouched
+        return Ok(document.to_string());
+    };
+
+    table.retain(|name, profile| {
+        !(name.starts_with(PROFILE_PREFIX) && is_generated(profile))
+            || profiles.iter().any(|(profile, _)| profile == name)
+    });
+    for (name, filter) in profiles {
+        let profile = table.entry(&name).or_insert_with(|| {
+            let mut profile = Table::new();
+            profile
+                .decor_mut()
+                .set_prefix(format!("\n{GENERATED_MARKER}\n"));
+            Item::Table(profile)
+        });
+        if profile.get("default-filter").and_then(Item::as_str)
+            != Some(filter.as_str())
+        {
+            profile["default-filter"] = value(filter);
+        }
+    }
+    let refreshed = document.to_string();
+    if content.is_empty() {
+        // no blank line before the first profile of a new file
+        return Ok(refreshed.trim_start_matches('\n').to_string());
+    }
+    Ok(refreshed)
+}
+
+/// Check if a profile was generated, i.e. is preceded by the marker comment
+fn is_generated(profile: &Item) -> bool {
+    profile
+        .as_table()
+        .and_then(|profile| profile.decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .is_some_and(|prefix| prefix.contains(GENERATED_MARKER))
+}
+
+/// Profiles (name and filterset) of the tags, then of the tag groups
+fn pinny_profiles(config: &Config) -> Vec<(String, String)> {
+    let tags = config.allowed_tags.iter().map(|tag| {
+        let expr = Expr::Tag(tag.clone());
+        (format!("{PROFILE_PREFIX}{tag}"), expr.to_nextest())
+    });
+    let groups = config.groups.iter().filter_map(|group| {
+        let expr =
+            group.tags.iter().map(|tag| Expr::Tag(tag.clone())).reduce(
+                |left, right| Expr::Or(Box::new(left), Box::new(right)),
+            )?;
+        Some((
+            format!("{PROFILE_PREFIX}group-{}", group.name),
+            expr.to_nextest(),
+        ))
+    });
+    tags.chain(groups).collect()
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> Error {
+    Error::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
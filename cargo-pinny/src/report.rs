@@ -0,0 +1,345 @@
+//! `cargo pinny report`: per-tag counts and durations of a test run.
+//!
+//! The results are read either from the libtest JSON output
+//! (`cargo test -- -Z unstable-options --format json --report-time`)
+//! or from a nextest JUnit report (as is or enriched by `cargo pinny junit`),
+//! told apart by their content.
+use crate::error::Error;
+use crate::junit;
+use pinny_core::mangling;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Output format of the report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Aligned table, for humans
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Test results, libtest JSON or JUnit (the standard input by default)
+    pub input: Option<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    pub format: Format,
+    /// Output file, the standard output by default
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+/// Outcome of a test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// Result of a tagged test
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    /// Original path of the test (e.g. `tests::test_hello`)
+    pub path: String,
+    pub tags: Vec<String>,
+    pub outcome: Outcome,
+    /// Duration in seconds, when reported
+    pub duration: Option<f64>,
+}
+
+/// Statistics of the tests of a tag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagStats {
+    pub tag: String,
+    pub tests: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    /// Sum of the test durations, in seconds
+    pub total: f64,
+    /// 95th percentile of the test durations, in seconds
+    pub p95: Option<f64>,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let input = read_input(args.input.as_ref())?;
+    let results = parse(&input).map_err(|error| {
+        let path = args.input.clone().unwrap_or_else(|| "<stdin>".into());
+        Error::InvalidTestResults(path, error)
+    })?;
+    if results.is_empty() {
+        eprintln!("warning: no tagged test found");
+    } else if results.iter().all(|result| result.duration.is_none()) {
+        eprintln!(
+            "warning: no test duration found (libtest reports them with `--report-time`)"
+        );
+    }
+
+    let stats = tag_stats(&results);
+    let output = match args.format {
+        Format::Table => render_table(&stats),
+        Format::Csv => render_csv(&stats),
+        Format::Json => render_json(&stats),
+    };
+    if let Some(path) = &args.output {
+        std::fs::write(path, output).map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        })
+    } else {
+        print!("{output}");
+        Ok(())
+    }
+}
+
+/// Parse the results of the tagged tests, either from a JUnit report
+/// or from the libtest JSON output (untagged tests are skipped)
+pub fn parse(input: &str) -> Result<Vec<TestResult>, String> {
+    if input.trim_start().starts_with('<') {
+        parse_junit(input)
+    } else {
+        Ok(parse_libtest_json(input))
+    }
+}
+
+/// Parse the libtest JSON output, that is one event per line.
+///
+/// Lines not being a JSON test event (e.g. the output of a build) are skipped.
+pub fn parse_libtest_json(input: &str) -> Vec<TestResult> {
+    input
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|event| event["type"] == "test")
+        .filter_map(|event| {
+            let outcome = match event["event"].as_str()? {
+                "ok" => Outcome::Passed,
+                "failed" => Outcome::Failed,
+                "ignored" => Outcome::Ignored,
+                _ => return None,
+            };
+            let test = mangling::decode(event["name"].as_str()?)?;
+            Some(TestResult {
+                path: test.path(),
+                tags: test.tags,
+                outcome,
+                duration: event["exec_time"].as_f64(),
+            })
+        })
+        .collect()
+}
+
+/// Parse a JUnit report (e.g. from nextest), where a test case is failed
+/// when having a `<failure>` or an `<error>`, and ignored when `<skipped>`.
+///
+/// Reports already enriched by `cargo pinny junit` are supported too,
+/// the tags being read from the properties or the classname of the test cases.
+pub fn parse_junit(input: &str) -> Result<Vec<TestResult>, String> {
+    let mut reader = Reader::from_str(input);
+    let mut results = Vec::new();
+    let mut current: Option<TestResult> = None;
+    loop {
+        match reader.read_event().map_err(|error| error.to_string())? {
+            Event::Eof => break,
+            Event::Start(element) if element.name().as_ref() == b"testcase" => {
+                current = Some(testcase(&element)?);
+            }
+            Event::Empty(element) if element.name().as_ref() == b"testcase" => {
+                results.push(testcase(&element)?);
+            }
+            Event::Start(element) | Event::Empty(element) => {
+                if let Some(result) = current.as_mut() {
+                    match element.name().as_ref() {
+                        b"failure" | b"error" => {
+                            result.outcome = Outcome::Failed;
+                        }
+                        b"skipped" => result.outcome = Outcome::Ignored,
+                        b"property" => {
+                            result.tags.extend(tag_property(&element)?);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(element) if element.name().as_ref() == b"testcase" => {
+                results.extend(current.take());
+            }
+            _ => {}
+        }
+    }
+    // untagged tests
+    results.retain(|result| !result.tags.is_empty());
+    Ok(results)
+}
+
+/// Passed result of a test case, with the tags decoded from its name,
+/// or else read from its classname (none when not tagged)
+fn testcase(element: &BytesStart) -> Result<TestResult, String> {
+    let attributes = junit::attributes(element)?;
+    let attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(each, _)| each == key)
+            .map(|(_, value)| value.as_str())
+    };
+    let name = attribute("name").unwrap_or_default();
+    let classname = attribute("classname");
+    let (path, tags) =
+        if let Some((test, _)) = junit::decode_testcase(name, classname) {
+            (test.path(), test.tags)
+        } else {
+            let tags = classname
+                .and_then(junit::split_classname)
+                .map(|(tags, _)| tags)
+                .unwrap_or_default();
+            (name.to_string(), tags)
+        };
+    Ok(TestResult {
+        path,
+        tags,
+        outcome: Outcome::Passed,
+        duration: attribute("time").and_then(|time| time.parse().ok()),
+    })
+}
+
+/// Tag reported by a `<property>` of a test case, if any
+fn tag_property(element: &BytesStart) -> Result<Option<String>, String> {
+    let attributes = junit::attributes(element)?;
+    let attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(each, _)| each == key)
+            .map(|(_, value)| value.clone())
+    };
+    Ok(attribute("name")
+        .filter(|name| name == junit::TAG_PROPERTY)
+        .and(attribute("value")))
+}
+
+/// Aggregate the results by tag, sorted by total duration (the most
+/// expensive tags first), then by tag name
+pub fn tag_stats(results: &[TestResult]) -> Vec<TagStats> {
+    let mut by_tag: BTreeMap<&str, (TagStats, Vec<f64>)> = BTreeMap::new();
+    for result in results {
+        for tag in &result.tags {
+            let (stats, durations) = by_tag.entry(tag).or_default();
+            stats.tests += 1;
+            match result.outcome {
+                Outcome::Passed => stats.passed += 1,
+                Outcome::Failed => stats.failed += 1,
+                Outcome::Ignored => stats.ignored += 1,
+            }
+            durations.extend(result.duration);
+        }
+    }
+
+    let mut stats: Vec<TagStats> = by_tag
+        .into_iter()
+        .map(|(tag, (stats, durations))| TagStats {
+            tag: tag.to_string(),
+            // `sum` of no `f64` is `-0.0`
+            total: durations.iter().fold(0.0, |total, each| total + each),
+            p95: percentile(durations, 95),
+            ..stats
+        })
+        .collect();
+    stats.sort_by(|left, right| right.total.total_cmp(&left.total));
+    stats
+}
+
+/// Percentile of some values, with the nearest-rank method
+pub fn percentile(mut values: Vec<f64>, percent: usize) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let rank = (values.len() * percent).div_ceil(100).max(1);
+    values.get(rank - 1).copied()
+}
+
+pub fn render_table(stats: &[TagStats]) -> String {
+    let width = stats
+        .iter()
+        .map(|stats| stats.tag.len())
+        .chain(["tag".len()])
+        .max()
+        .unwrap_or_default();
+    let mut output = format!(
+        "{:width$}  {:>5}  {:>6}  {:>7}  {:>10}  {:>10}\n",
+        "tag", "tests", "failed", "ignored", "total (s)", "p95 (s)"
+    );
+    for stats in stats {
+        let p95 = stats
+            .p95
+            .map_or_else(|| "-".into(), |p95| format!("{p95:.3}"));
+        let _ = writeln!(
+            output,
+            "{:width$}  {:>5}  {:>6}  {:>7}  {:>10.3}  {:>10}",
+            stats.tag,
+            stats.tests,
+            stats.failed,
+            stats.ignored,
+            stats.total,
+            p95
+        );
+    }
+    output
+}
+
+pub fn render_csv(stats: &[TagStats]) -> String {
+    let mut output =
+        String::from("tag,tests,passed,failed,ignored,total_secs,p95_secs\n");
+    for stats in stats {
+        let p95 = stats.p95.map(|p95| p95.to_string()).unwrap_or_default();
+        let _ = writeln!(
+            output,
+            "{},{},{},{},{},{},{p95}",
+            stats.tag,
+            stats.tests,
+            stats.passed,
+            stats.failed,
+            stats.ignored,
+            stats.total
+        );
+    }
+    output
+}
+
+pub fn render_json(stats: &[TagStats]) -> String {
+    let stats: Vec<serde_json::Value> = stats
+        .iter()
+        .map(|stats| {
+            serde_json::json!({
+                "tag": stats.tag,
+                "tests": stats.tests,
+                "passed": stats.passed,
+                "failed": stats.failed,
+                "ignored": stats.ignored,
+                "total_secs": stats.total,
+                "p95_secs": stats.p95,
+            })
+        })
+        .collect();
+    format!("{}\n", serde_json::Value::Array(stats))
+}
+
+fn read_input(path: Option<&PathBuf>) -> Result<String, Error> {
+    let mut input = String::new();
+    let result = match path {
+        Some(path) => std::fs::File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut input)),
+        None => std::io::stdin().read_to_string(&mut input),
+    };
+    result.map_err(|source| Error::Io {
+        path: path.cloned().unwrap_or_else(|| "<stdin>".into()),
+        source,
+    })?;
+    Ok(input)
+}
@@ -0,0 +1,197 @@
+//! Scan of the sources of a package, looking for the pinny tag attributes
+//! and the test functions.
+//!
+//! Sources are parsed with `syn`, without expanding any macro: only the
+//! attributes written as `#[tag(...)]` or `#[pinny::tag(...)]` are detected,
+//! the same way the `#[tag]` macro detects them on the items of a tagged module.
+use crate::error::Error;
+use pinny_core::attributes;
+use std::path::{Path, PathBuf};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, Ident, ItemFn, ItemMod, Meta, Token};
+
+/// Directories of a package holding the sources of the tests
+pub const SOURCE_DIRS: [&str; 4] = ["src", "tests", "benches", "examples"];
+
+/// Result of the scan of some sources
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Scan {
+    pub tag_uses: Vec<TagUse>,
+    pub tests: Vec<TestFn>,
+}
+
+/// A tag attribute, as written in the sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagUse {
+    pub file: PathBuf,
+    pub line: usize,
+    /// Tag labels (i.e. tags or aliases) of the attribute
+    pub labels: Vec<String>,
+}
+
+/// A test function, as written in the sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFn {
+    pub file: PathBuf,
+    pub line: usize,
+    /// Path of the function within the file (e.g. `tests::test_hello`)
+    pub path: String,
+    /// Tagged by its own attribute or by an enclosing module
+    pub tagged: bool,
+}
+
+/// Scan the sources of the package in the given directory
+pub fn scan_package(package_dir: &Path) -> Result<Scan, Error> {
+    let mut files = Vec::new();
+    for dir in SOURCE_DIRS {
+        // paths relative to the current directory, when scanning it
+        let dir = if package_dir == Path::new(".") {
+            PathBuf::from(dir)
+        } else {
+            package_dir.join(dir)
+        };
+        rust_files(&dir, &mut files)?;
+    }
+    files.sort();
+
+    let mut scan_result = Scan::default();
+    for file in files {
+        let content =
+            std::fs::read_to_string(&file).map_err(|source| Error::Io {
+                path: file.clone(),
+                source,
+            })?;
+        // e.g. the invalid sources of compile-fail tests
+        match scan(&file, &content) {
+            Ok(file_scan) => {
+                scan_result.tag_uses.extend(file_scan.tag_uses);
+                scan_result.tests.extend(file_scan.tests);
+            }
+            Err(error) => eprintln!("warning: {error}, skipped"),
+        }
+    }
+    Ok(scan_result)
+}
+
+/// Scan the content of a source file
+pub fn scan(file: &Path, content: &str) -> Result<Scan, Error> {
+    let ast = syn::parse_file(content).map_err(|error| {
+        Error::InvalidSource(file.to_path_buf(), error.to_string())
+    })?;
+    let mut visitor = SourceVisitor {
+        file,
+        modules: Vec::new(),
+        scan: Scan::default(),
+    };
+    visitor.visit_file(&ast);
+    Ok(visitor.scan)
+}
+
+/// Check if an attribute is a tag attribute, e.g. `#[tag(...)]` or `#[pinny::tag(...)]`
+pub fn is_tag_attr(attr: &Attribute) -> bool {
+    !matches!(attr.meta, Meta::NameValue(_))
+        && attributes::is_tag_attr(&path_segments(attr))
+}
+
+/// Check if an attribute is a test attribute, either `#[test]` or a third-party
+/// one (e.g. `#[tokio::test]`, `#[rstest]`, see [`attributes::THIRD_PARTY_TEST_ATTRS`])
+pub fn is_test_attr(attr: &Attribute) -> bool {
+    attributes::is_any_test_attr(&path_segments(attr))
+}
+
+/// Return the segments of the path of an attribute, e.g. `["tokio", "test"]`
+fn path_segments(attr: &Attribute) -> Vec<String> {
+    attr.path()
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
+
+/// Collect the `.rs` files of a directory, recursively
+fn rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let io_error = |source| Error::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+    for entry in std::fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.is_dir() {
+            rust_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+struct SourceVisitor<'a> {
+    file: &'a Path,
+    /// Enclosing inline modules, with whether they are tagged
+    modules: Vec<(String, bool)>,
+    scan: Scan,
+}
+
+impl SourceVisitor<'_> {
+    /// Collect the tag attributes, returning whether there is any
+    fn visit_attrs(&mut self, attrs: &[Attribute]) -> bool {
+        let mut tagged = false;
+        for attr in attrs.iter().filter(|attr| is_tag_attr(attr)) {
+            // malformed attributes are reported by the macro itself
+            let labels = attr
+                .parse_args_with(
+                    Punctuated::<Ident, Token![,]>::parse_terminated,
+                )
+                .map(|labels| labels.iter().map(ToString::to_string).collect())
+                .unwrap_or_default();
+            self.scan.tag_uses.push(TagUse {
+                file: self.file.to_path_buf(),
+                line: attr.span().start().line,
+                labels,
+            });
+            tagged = true;
+        }
+        tagged
+    }
+}
+
+impl<'ast> Visit<'ast> for SourceVisitor<'_> {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        let tagged = self.visit_attrs(&item.attrs);
+        if item.attrs.iter().any(is_test_attr) {
+            let path = self
+                .modules
+                .iter()
+                .map(|(name, _)| name.clone())
+                .chain([item.sig.ident.to_string()])
+                .collect::<Vec<_>>()
+                .join("::");
+            // the enclosing tagged modules only tag the tests the macro
+            // handles (e.g. not the `#[rstest]` ones)
+            let inherited = self.modules.iter().any(|(_, tagged)| *tagged)
+                && item
+                    .attrs
+                    .iter()
+                    .any(|attr| attributes::is_test_attr(&path_segments(attr)));
+            self.scan.tests.push(TestFn {
+                file: self.file.to_path_buf(),
+                line: item.sig.ident.span().start().line,
+                path,
+                tagged: tagged || inherited,
+            });
+        }
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        let tagged = self.visit_attrs(&item.attrs);
+        self.modules.push((item.ident.to_string(), tagged));
+        visit::visit_item_mod(self, item);
+        self.modules.pop();
+    }
+}
@@ -0,0 +1,95 @@
+use crate::junit::*;
+
+const NEXTEST_REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run" tests="3" failures="1" errors="0">
+    <testsuite name="my-crate" tests="3" disabled="0" errors="0" failures="1">
+        <testcase name="tests::test_hello::t::slow::bitcoin::t" classname="my-crate" timestamp="2024-01-01T00:00:00.000+00:00" time="0.010">
+            <failure type="test failure">assertion failed</failure>
+        </testcase>
+        <testcase name="tests::test_world::t::slow::t" classname="my-crate" time="0.002"/>
+        <testcase name="tests::test_untagged" classname="my-crate" time="0.001"/>
+    </testsuite>
+</testsuites>
+"#;
+
+#[test]
+fn test_enrich_nextest_properties() {
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run" tests="3" failures="1" errors="0">
+    <testsuite name="my-crate" tests="3" disabled="0" errors="0" failures="1">
+        <testcase name="tests::test_hello" classname="my-crate" timestamp="2024-01-01T00:00:00.000+00:00" time="0.010"><properties><property name="tag" value="slow"/><property name="tag" value="bitcoin"/></properties>
+            <failure type="test failure">assertion failed</failure>
+        </testcase>
+        <testcase name="tests::test_world" classname="my-crate" time="0.002"><properties><property name="tag" value="slow"/></properties></testcase>
+        <testcase name="tests::test_untagged" classname="my-crate" time="0.001"/>
+    </testsuite>
+</testsuites>
+"#;
+    assert_eq!(
+        expected,
+        enrich(NEXTEST_REPORT, TagsIn::Properties).unwrap()
+    );
+}
+
+#[test]
+fn test_enrich_nextest_classname() {
+    let enriched = enrich(NEXTEST_REPORT, TagsIn::Classname).unwrap();
+    assert!(enriched.contains(
+        r#"<testcase name="tests::test_hello" classname="[slow, bitcoin] my-crate" timestamp="#
+    ));
+    assert!(enriched.contains(
+        r#"<testcase name="tests::test_world" classname="[slow] my-crate" time="0.002"/>"#
+    ));
+    assert!(enriched.contains(
+        r#"<testcase name="tests::test_untagged" classname="my-crate" time="0.001"/>"#
+    ));
+    assert!(!enriched.contains("<properties>"));
+}
+
+#[test]
+fn test_enrich_libtest() {
+    let report = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><testsuites><testsuite name="test" package="test" id="0" errors="0" failures="0" tests="3" skipped="0" >"#,
+        r#"<testcase classname="tests::nested::test_hello::t::slow" name="t" time="0"/>"#,
+        r#"<testcase classname="test_root::t::slow" name="t" time="0"/>"#,
+        r#"<testcase classname="integration" name="test_world::t::bitcoin::t" time="0"/>"#,
+        r#"<system-out/><system-err/></testsuite></testsuites>"#,
+    );
+    let expected = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><testsuites><testsuite name="test" package="test" id="0" errors="0" failures="0" tests="3" skipped="0" >"#,
+        r#"<testcase classname="tests::nested" name="test_hello" time="0"><properties><property name="tag" value="slow"/></properties></testcase>"#,
+        r#"<testcase classname="crate" name="test_root" time="0"><properties><property name="tag" value="slow"/></properties></testcase>"#,
+        r#"<testcase classname="integration" name="test_world" time="0"><properties><property name="tag" value="bitcoin"/></properties></testcase>"#,
+        r#"<system-out/><system-err/></testsuite></testsuites>"#,
+    );
+    assert_eq!(expected, enrich(report, TagsIn::Properties).unwrap());
+}
+
+#[test]
+fn test_enrich_escaped_attributes() {
+    let report =
+        r#"<testcase name="test_hello::t::slow::t" classname="a &amp; b"/>"#;
+    assert_eq!(
+        r#"<testcase name="test_hello" classname="[slow] a &amp; b"/>"#,
+        enrich(report, TagsIn::Classname).unwrap()
+    );
+}
+
+#[test]
+fn test_enrich_invalid_report_ko() {
+    assert!(enrich(
+        "<testsuites><testcase name=\"x></testsuites>",
+        TagsIn::Properties
+    )
+    .is_err());
+}
+
+#[test]
+fn test_split_classname() {
+    assert_eq!(
+        Some((vec!["slow".into(), "bitcoin".into()], "my-crate")),
+        split_classname("[slow, bitcoin] my-crate")
+    );
+    assert_eq!(Some((vec!["slow".into()], "")), split_classname("[slow]"));
+    assert_eq!(None, split_classname("my-crate"));
+}
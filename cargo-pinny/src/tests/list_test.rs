@@ -0,0 +1,64 @@
+use crate::list::*;
+use pinny_core::config::{Config, TagDefinition};
+use pinny_core::mangling::{decode, TaggedTest};
+
+fn tagged_tests() -> Vec<TaggedTest> {
+    [
+        "tests::test_hello::t::tag1::tag2::t",
+        "tests::test_world::t::tag1::t",
+        "test_slow::t::tag3::t",
+    ]
+    .iter()
+    .map(|path| decode(path).unwrap())
+    .collect()
+}
+
+#[test]
+fn test_render() {
+    let expected = "\
+tests::test_hello: tag1, tag2
+tests::test_world: tag1
+test_slow: tag3
+";
+    assert_eq!(expected, render(&tagged_tests()));
+    assert_eq!("", render(&[]));
+}
+
+#[test]
+fn test_render_by_tag() {
+    let expected = "\
+tag1: 2 tests
+    tests::test_hello
+    tests::test_world
+tag2: 1 test
+    tests::test_hello
+tag3: 1 test
+    test_slow
+";
+    assert_eq!(expected, render_by_tag(&tagged_tests(), None));
+}
+
+#[test]
+fn test_render_by_tag_with_descriptions() {
+    let mut config = Config::default();
+    for (tag, description) in [("tag1", "Fast unit tests"), ("tag3", "Slow")] {
+        config.definitions.insert(
+            tag.into(),
+            TagDefinition {
+                description: Some(description.into()),
+                ..TagDefinition::default()
+            },
+        );
+    }
+
+    let expected = "\
+tag1: 2 tests - Fast unit tests
+    tests::test_hello
+    tests::test_world
+tag2: 1 test
+    tests::test_hello
+tag3: 1 test - Slow
+    test_slow
+";
+    assert_eq!(expected, render_by_tag(&tagged_tests(), Some(&config)));
+}
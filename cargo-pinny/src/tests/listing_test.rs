@@ -0,0 +1,72 @@
+use crate::listing::*;
+
+#[test]
+fn test_parse_terse() {
+    let output = "\
+tests::test_hello::t::tag1::t: test
+tests::test_world: test
+benches::bench_hello::t::tag1::t: benchmark
+src/lib.rs - (line 5): test
+";
+    assert_eq!(
+        vec![
+            "tests::test_hello::t::tag1::t",
+            "tests::test_world",
+            "src/lib.rs - (line 5)",
+        ],
+        parse_terse(output)
+    );
+}
+
+#[test]
+fn test_parse_nextest() {
+    let output = r#"{
+        "test-count": 3,
+        "rust-suites": {
+            "pinny": {
+                "binary-id": "pinny",
+                "testcases": {
+                    "tests::test_hello::t::tag1::t": { "ignored": false },
+                    "tests::test_world": { "ignored": false }
+                }
+            },
+            "pinny::nominal": {
+                "binary-id": "pinny::nominal",
+                "testcases": {
+                    "test_slow::t::tag2::t": { "ignored": true }
+                }
+            }
+        }
+    }"#;
+    assert_eq!(
+        vec![
+            "tests::test_hello::t::tag1::t",
+            "tests::test_world",
+            "test_slow::t::tag2::t",
+        ],
+        parse_nextest(output).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_nextest_ko() {
+    let error = parse_nextest("not json").unwrap_err();
+    assert!(matches!(error, crate::error::Error::InvalidNextestList(_)));
+
+    let error = parse_nextest(r#"{"rust-suites": {"pinny": {}}}"#).unwrap_err();
+    assert_eq!(
+        "Invalid nextest test list: missing 'testcases' of 'pinny'",
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_decode_listed_tests() {
+    let test = Test::new("tests::test_hello::t::tag1::tag2::t");
+    let tagged = test.tagged.unwrap();
+    assert_eq!("tests::test_hello", tagged.path());
+    assert_eq!(vec!["tag1", "tag2"], tagged.tags);
+
+    assert_eq!(None, Test::new("tests::test_world").tagged);
+    assert_eq!(None, Test::new("src/lib.rs - (line 5)").tagged);
+}
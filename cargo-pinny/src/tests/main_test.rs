@@ -0,0 +1,51 @@
+use crate::Cargo;
+use clap::{CommandFactory, Parser};
+
+#[test]
+fn test_cli() {
+    Cargo::command().debug_assert();
+}
+
+#[test]
+fn test_parse_cargo_args() {
+    let Cargo::Pinny(pinny) = Cargo::parse_from([
+        "cargo", "pinny", "list", "--by-tag", "--", "-p", "pinny",
+    ]);
+    let crate::Command::List(args) = pinny.command else {
+        panic!("expected the list command");
+    };
+    assert!(args.by_tag);
+    assert!(!args.listing.nextest);
+    assert_eq!(vec!["-p", "pinny"], args.listing.cargo_args);
+}
+
+#[test]
+fn test_parse_list_describe() {
+    let Cargo::Pinny(pinny) =
+        Cargo::parse_from(["cargo", "pinny", "list", "--by-tag", "--describe"]);
+    let crate::Command::List(args) = pinny.command else {
+        panic!("expected the list command");
+    };
+    assert!(args.describe);
+
+    // descriptions are only shown by tag
+    let result =
+        Cargo::try_parse_from(["cargo", "pinny", "list", "--describe"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_filter() {
+    let Cargo::Pinny(pinny) = Cargo::parse_from([
+        "cargo",
+        "pinny",
+        "filter",
+        "--libtest",
+        "tag1 and not tag2",
+    ]);
+    let crate::Command::Filter(args) = pinny.command else {
+        panic!("expected the filter command");
+    };
+    assert!(args.libtest);
+    assert_eq!("tag1 and not tag2", args.expression);
+}
@@ -0,0 +1,9 @@
+mod junit_test;
+mod list_test;
+mod listing_test;
+mod main_test;
+mod nextest_config_test;
+mod report_test;
+mod sources_test;
+mod untagged_test;
+mod unused_test;
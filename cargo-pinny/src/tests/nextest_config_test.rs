@@ -0,0 +1,140 @@
+use crate::nextest_config::*;
+use pinny_core::config::{Config, GroupRule, TagGroup};
+use pinny_core::expression::Expr;
+
+fn config() -> Config {
+    Config {
+        allowed_tags: vec!["slow".into(), "bitcoin".into()],
+        groups: vec![TagGroup {
+            name: "speed".into(),
+            tags: vec!["slow".into(), "fast".into()],
+            rule: GroupRule::AtMostOne,
+        }],
+        ..Config::default()
+    }
+}
+
+fn filter(expr: &str) -> String {
+    Expr::parse(expr).unwrap().to_nextest()
+}
+
+#[test]
+fn test_refresh_empty() {
+    let expected = format!(
+        r#"# Generated by `cargo pinny nextest-config`
+[profile.pinny-slow]
+default-filter = "{}"
+
+# Generated by `cargo pinny nextest-config`
+[profile.pinny-bitcoin]
+default-filter = "{}"
+
+# Generated by `cargo pinny nextest-config`
+[profile.pinny-group-speed]
+default-filter = "{}"
+"#,
+        filter("slow"),
+        filter("bitcoin"),
+        filter("slow or fast")
+    );
+    let refreshed = refresh("", &config()).unwrap();
+    assert_eq!(expected, refreshed);
+
+    // idempotent
+    assert_eq!(refreshed, refresh(&refreshed, &config()).unwrap());
+}
+
+#[test]
+fn test_refresh_keeps_user_sections() {
+    let content = format!(
+        r#"# nextest config
+[store]
+dir = "target/nextest"
+
+[profile.default]
+retries = 2 # flaky network
+
+[profile.pinny-slow]
+default-filter = "{}"
+slow-timeout = "5m"
+
+# Generated by `cargo pinny nextest-config`
+[profile.pinny-removed]
+default-filter = "test(/removed/)"
+
+[profile.ci]
+fail-fast = false
+
+# hand written
+[profile.pinny-ci]
+default-filter = "all()"
+"#,
+        filter("slow")
+    );
+    let expected = format!(
+        r#"# nextest config
+[store]
+dir = "target/nextest"
+
+[profile.default]
+retries = 2 # flaky network
+
+[profile.pinny-slow]
+default-filter = "{}"
+slow-timeout = "5m"
+
+[profile.ci]
+fail-fast = false
+
+# hand written
+[profile.pinny-ci]
+default-filter = "all()"
+
+# Generated by `cargo pinny nextest-config`
+[profile.pinny-bitcoin]
+default-filter = "{}"
+
+# Generated by `cargo pinny nextest-config`
+[profile.pinny-group-speed]
+default-filter = "{}"
+"#,
+        filter("slow"),
+        filter("bitcoin"),
+        filter("slow or fast")
+    );
+    assert_eq!(expected, refresh(&content, &config()).unwrap());
+}
+
+#[test]
+fn test_refresh_outdated_filter() {
+    let content = r#"[profile.pinny-slow]
+default-filter = "test(:slow:)" # hand written
+"#;
+    let refreshed = refresh(content, &config()).unwrap();
+    assert!(refreshed.starts_with(&format!(
+        "[profile.pinny-slow]\ndefault-filter = \"{}\"\n",
+        filter("slow")
+    )));
+}
+
+#[test]
+fn test_refresh_invalid_toml_ko() {
+    assert!(refresh("[profile", &config()).is_err());
+}
+
+#[test]
+fn test_refresh_keeps_user_pinny_profiles() {
+    let content = r#"[profile.pinny-ci]
+default-filter = "all()"
+fail-fast = false
+"#;
+    let refreshed = refresh(content, &config()).unwrap();
+    assert!(refreshed.starts_with(content));
+
+    // still kept once the generated profiles are removed
+    let config = Config {
+        allowed_tags: vec![],
+        ..Config::default()
+    };
+    assert_eq!(content, refresh(&refreshed, &config).unwrap());
+}
@@ -0,0 +1,205 @@
+use crate::junit::{self, TagsIn};
+use crate::report::*;
+
+fn result(
+    path: &str,
+    tags: &[&str],
+    outcome: Outcome,
+    duration: f64,
+) -> TestResult {
+    TestResult {
+        path: path.into(),
+        tags: tags.iter().map(ToString::to_string).collect(),
+        outcome,
+        duration: Some(duration),
+    }
+}
+
+#[test]
+fn test_parse_libtest_json() {
+    let input = r#"
+   Compiling my-crate v0.1.0
+{ "type": "suite", "event": "started", "test_count": 4 }
+{ "type": "test", "event": "started", "name": "tests::test_hello::t::slow::bitcoin::t" }
+{ "type": "test", "name": "tests::test_hello::t::slow::bitcoin::t", "event": "ok", "exec_time": 1.5 }
+{ "type": "test", "name": "test_world::t::slow::t", "event": "failed", "exec_time": 0.25, "stdout": "boom" }
+{ "type": "test", "name": "test_ignored::t::slow::t", "event": "ignored" }
+{ "type": "test", "name": "tests::test_untagged", "event": "ok", "exec_time": 0.1 }
+{ "type": "suite", "event": "failed", "passed": 2, "failed": 1, "ignored": 1, "exec_time": 1.9 }
+"#;
+    assert_eq!(
+        vec![
+            result(
+                "tests::test_hello",
+                &["slow", "bitcoin"],
+                Outcome::Passed,
+                1.5
+            ),
+            result("test_world", &["slow"], Outcome::Failed, 0.25),
+            TestResult {
+                duration: None,
+                ..result("test_ignored", &["slow"], Outcome::Ignored, 0.0)
+            },
+        ],
+        parse(input).unwrap()
+    );
+}
+
+const JUNIT_REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run" tests="4" failures="1" errors="1">
+    <testsuite name="my-crate" tests="4" disabled="0" errors="1" failures="1">
+        <testcase name="tests::test_hello::t::slow::bitcoin::t" classname="my-crate" time="1.500"/>
+        <testcase name="test_world::t::slow::t" classname="my-crate" time="0.250">
+            <failure type="test failure">assertion failed</failure>
+            <system-out>boom</system-out>
+        </testcase>
+        <testcase name="test_error::t::bitcoin::t" classname="my-crate" time="0.125">
+            <error type="test abort"/>
+        </testcase>
+        <testcase name="test_untagged" classname="my-crate" time="0.100"/>
+    </testsuite>
+</testsuites>
+"#;
+
+#[test]
+fn test_parse_junit() {
+    assert_eq!(
+        vec![
+            result(
+                "tests::test_hello",
+                &["slow", "bitcoin"],
+                Outcome::Passed,
+                1.5
+            ),
+            result("test_world", &["slow"], Outcome::Failed, 0.25),
+            result("test_error", &["bitcoin"], Outcome::Failed, 0.125),
+        ],
+        parse(JUNIT_REPORT).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_enriched_junit() {
+    for tags_in in [TagsIn::Properties, TagsIn::Classname] {
+        let enriched = junit::enrich(JUNIT_REPORT, tags_in).unwrap();
+        assert_eq!(parse(JUNIT_REPORT), parse(&enriched));
+    }
+}
+
+#[test]
+fn test_parse_libtest_junit() {
+    let input = r#"<testsuites><testsuite name="test"><testcase classname="tests::test_hello::t::slow" name="t" time="0"><skipped/></testcase></testsuite></testsuites>"#;
+    assert_eq!(
+        vec![result(
+            "tests::test_hello",
+            &["slow"],
+            Outcome::Ignored,
+            0.0
+        )],
+        parse(input).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_invalid_junit_ko() {
+    assert!(parse("<testsuites><testcase name=\"x></testsuites>").is_err());
+}
+
+#[test]
+fn test_percentile() {
+    assert_eq!(None, percentile(vec![], 95));
+    assert_eq!(Some(3.0), percentile(vec![3.0], 95));
+    let values: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+    assert_eq!(Some(95.0), percentile(values.clone(), 95));
+    assert_eq!(Some(50.0), percentile(values, 50));
+    assert_eq!(Some(2.0), percentile(vec![1.0, 2.0], 95));
+}
+
+fn stats() -> Vec<TagStats> {
+    tag_stats(&[
+        result("test_1", &["slow", "bitcoin"], Outcome::Passed, 1.5),
+        result("test_2", &["slow"], Outcome::Failed, 0.5),
+        TestResult {
+            duration: None,
+            ..result("test_3", &["slow", "flaky"], Outcome::Ignored, 0.0)
+        },
+        result("test_4", &["bitcoin"], Outcome::Passed, 0.25),
+    ])
+}
+
+#[test]
+fn test_tag_stats() {
+    assert_eq!(
+        vec![
+            TagStats {
+                tag: "slow".into(),
+                tests: 3,
+                passed: 1,
+                failed: 1,
+                ignored: 1,
+                total: 2.0,
+                p95: Some(1.5),
+            },
+            TagStats {
+                tag: "bitcoin".into(),
+                tests: 2,
+                passed: 2,
+                failed: 0,
+                ignored: 0,
+                total: 1.75,
+                p95: Some(1.5),
+            },
+            TagStats {
+                tag: "flaky".into(),
+                tests: 1,
+                passed: 0,
+                failed: 0,
+                ignored: 1,
+                total: 0.0,
+                p95: None,
+            },
+        ],
+        stats()
+    );
+}
+
+#[test]
+fn test_render_table() {
+    let expected = "\
+tag      tests  failed  ignored   total (s)     p95 (s)
+slow         3       1        1       2.000       1.500
+bitcoin      2       0        0       1.750       1.500
+flaky        1       0        1       0.000           -
+";
+    assert_eq!(expected, render_table(&stats()));
+}
+
+#[test]
+fn test_render_csv() {
+    let expected = "\
+tag,tests,passed,failed,ignored,total_secs,p95_secs
+slow,3,1,1,1,2,1.5
+bitcoin,2,2,0,0,1.75,1.5
+flaky,1,0,0,1,0,
+";
+    assert_eq!(expected, render_csv(&stats()));
+}
+
+#[test]
+fn test_render_json() {
+    let json: serde_json::Value =
+        serde_json::from_str(&render_json(&stats())).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "tag": "slow",
+            "tests": 3,
+            "passed": 1,
+            "failed": 1,
+            "ignored": 1,
+            "total_secs": 2.0,
+            "p95_secs": 1.5,
+        }),
+        json[0]
+    );
+    assert_eq!(serde_json::Value::Null, json[2]["p95_secs"]);
+}
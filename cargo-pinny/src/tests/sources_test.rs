@@ -0,0 +1,121 @@
+use crate::sources::*;
+use std::path::Path;
+
+fn labels(uses: &[TagUse]) -> Vec<Vec<&str>> {
+    uses.iter()
+        .map(|tag_use| tag_use.labels.iter().map(String::as_str).collect())
+        .collect()
+}
+
+#[test]
+fn test_scan() {
+    let content = r"
+use pinny::tag;
+
+#[tag(slow, bitcoin)]
+#[test]
+fn test_1() {}
+
+#[cfg(test)]
+#[pinny::tag(network)]
+mod tests {
+    #[tokio::test]
+    #[tag(slow)]
+    async fn test_2() {}
+
+    #[other::tag(ignored)]
+    #[test]
+    fn test_3() {}
+}
+";
+    let uses = scan(Path::new("src/lib.rs"), content).unwrap().tag_uses;
+    assert_eq!(
+        vec![vec!["slow", "bitcoin"], vec!["network"], vec!["slow"]],
+        labels(&uses)
+    );
+    assert_eq!(
+        vec![4, 9, 12],
+        uses.iter().map(|tag_use| tag_use.line).collect::<Vec<_>>()
+    );
+    assert!(uses
+        .iter()
+        .all(|tag_use| tag_use.file == Path::new("src/lib.rs")));
+}
+
+#[test]
+fn test_scan_malformed_tag() {
+    let content = r#"
+#[tag("slow")]
+#[test]
+fn test_1() {}
+"#;
+    let uses = scan(Path::new("src/lib.rs"), content).unwrap().tag_uses;
+    assert_eq!(vec![Vec::<&str>::new()], labels(&uses));
+}
+
+#[test]
+fn test_scan_invalid_source_ko() {
+    let error = scan(Path::new("src/lib.rs"), "fn {").unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("Failed to parse src/lib.rs: "));
+}
+
+#[test]
+fn test_scan_tests() {
+    let content = r#"
+#[test]
+fn test_untagged() {}
+
+#[tag(slow)]
+#[test]
+fn test_tagged() {}
+
+fn helper() {}
+
+#[tag(network)]
+mod tagged {
+    mod nested {
+        #[tokio::test]
+        async fn test_inherited() {}
+    }
+
+    #[rstest]
+    fn test_not_inherited() {}
+
+    #[tag(slow)]
+    #[rstest]
+    fn test_rstest_tagged() {}
+}
+
+mod tests {
+    #[rstest]
+    #[case(1)]
+    fn test_rstest(#[case] value: u32) {}
+
+    #[test_case(1 ; "one")]
+    fn test_case_1(value: u32) {}
+
+    #[test_log::test]
+    fn test_log() {}
+}
+"#;
+    let tests = scan(Path::new("src/lib.rs"), content).unwrap().tests;
+    let summary: Vec<(&str, usize, bool)> = tests
+        .iter()
+        .map(|test| (test.path.as_str(), test.line, test.tagged))
+        .collect();
+    assert_eq!(
+        vec![
+            ("test_untagged", 3, false),
+            ("test_tagged", 7, true),
+            ("tagged::nested::test_inherited", 15, true),
+            ("tagged::test_not_inherited", 19, false),
+            ("tagged::test_rstest_tagged", 23, true),
+            ("tests::test_rstest", 29, false),
+            ("tests::test_case_1", 32, false),
+            ("tests::test_log", 35, false),
+        ],
+        summary
+    );
+}
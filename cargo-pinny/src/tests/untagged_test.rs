@@ -0,0 +1,25 @@
+use crate::sources::TestFn;
+use crate::untagged::*;
+
+#[test]
+fn test_render() {
+    let tests = [
+        TestFn {
+            file: "src/lib.rs".into(),
+            line: 12,
+            path: "tests::test_hello".into(),
+            tagged: false,
+        },
+        TestFn {
+            file: "tests/world.rs".into(),
+            line: 3,
+            path: "test_world".into(),
+            tagged: false,
+        },
+    ];
+    let expected = "\
+src/lib.rs:12: tests::test_hello
+tests/world.rs:3: test_world
+";
+    assert_eq!(expected, render(&tests.iter().collect::<Vec<_>>()));
+}
@@ -0,0 +1,34 @@
+use crate::sources::TagUse;
+use crate::unused::*;
+use pinny_core::config::{Config, TagDefinition};
+
+fn tag_use(labels: &[&str]) -> TagUse {
+    TagUse {
+        file: "src/lib.rs".into(),
+        line: 1,
+        labels: labels.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[test]
+fn test_unused_tags() {
+    let mut config = Config {
+        allowed_tags: ["slow", "bitcoin", "network", "flaky", "db"]
+            .map(String::from)
+            .to_vec(),
+        ..Config::default()
+    };
+    config.aliases.insert("btc".into(), "bitcoin".into());
+    config.definitions.insert(
+        "bitcoin".into(),
+        TagDefinition {
+            implies: vec!["network".into()],
+            ..TagDefinition::default()
+        },
+    );
+
+    let uses = [tag_use(&["slow", "unknown"]), tag_use(&["btc"])];
+    assert_eq!(vec!["flaky", "db"], unused_tags(&config, &uses));
+
+    assert_eq!(config.allowed_tags, unused_tags(&config, &[]));
+}
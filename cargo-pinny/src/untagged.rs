@@ -0,0 +1,43 @@
+//! `cargo pinny untagged`: test functions carrying no tag.
+use crate::error::Error;
+use crate::sources::{self, TestFn};
+use pinny_core::config::ConfigFactory;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Directory of the package to scan
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub manifest_dir: PathBuf,
+    /// Fail when any test is untagged (implied by `require_tags = true`)
+    #[arg(long)]
+    pub deny: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let config = ConfigFactory::from_manifest_dir(&args.manifest_dir)?;
+    let scan = sources::scan_package(&args.manifest_dir)?;
+
+    let untagged: Vec<&TestFn> =
+        scan.tests.iter().filter(|test| !test.tagged).collect();
+    print!("{}", render(&untagged));
+    if (args.deny || config.require_tags) && !untagged.is_empty() {
+        return Err(Error::UntaggedTests(untagged.len()));
+    }
+    Ok(())
+}
+
+/// Render each test with its location, e.g. `src/lib.rs:12: tests::test_hello`
+pub fn render(tests: &[&TestFn]) -> String {
+    tests.iter().fold(String::new(), |mut output, test| {
+        let _ = writeln!(
+            output,
+            "{}:{}: {}",
+            test.file.display(),
+            test.line,
+            test.path
+        );
+        output
+    })
+}
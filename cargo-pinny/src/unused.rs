@@ -0,0 +1,58 @@
+//! `cargo pinny unused`: allowed tags no test uses anymore.
+use crate::error::Error;
+use crate::sources::{self, TagUse};
+use pinny_core::config::{Config, ConfigFactory};
+use pinny_core::validation;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Directory of the package to scan
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub manifest_dir: PathBuf,
+    /// Fail when any allowed tag is unused
+    #[arg(long)]
+    pub deny: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), Error> {
+    let config = ConfigFactory::from_manifest_dir(&args.manifest_dir)?;
+    let scan = sources::scan_package(&args.manifest_dir)?;
+
+    let unused = unused_tags(&config, &scan.tag_uses);
+    for tag in &unused {
+        println!("{tag}");
+    }
+    if args.deny && !unused.is_empty() {
+        return Err(Error::UnusedTags(unused.len()));
+    }
+    Ok(())
+}
+
+/// Return the allowed tags never used, in the order they are allowed.
+///
+/// A tag is used when written in a tag attribute (directly or by an alias),
+/// or implied by a used tag.
+pub fn unused_tags<'a>(config: &'a Config, uses: &[TagUse]) -> Vec<&'a str> {
+    let used_tags: HashSet<String> = uses
+        .iter()
+        .flat_map(|tag_use| {
+            // labels not allowed are reported by the macro itself
+            let labels: Vec<&String> = tag_use
+                .labels
+                .iter()
+                .filter(|label| config.is_allowed(label))
+                .collect();
+            validation::expand_labels(&labels, config)
+        })
+        .map(|(tag, _)| tag)
+        .collect();
+
+    config
+        .allowed_tags
+        .iter()
+        .filter(|tag| !used_tags.contains(*tag))
+        .map(String::as_str)
+        .collect()
+}
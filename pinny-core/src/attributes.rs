@@ -0,0 +1,49 @@
+//! Attributes recognized by the `#[tag]` macro, shared with the tools
+//! scanning the sources (e.g. `cargo pinny untagged`), so that both agree
+//! on which functions are tests and which ones are tagged.
+//!
+//! Attributes are given by the segments of their path,
+//! e.g. `["tokio", "test"]` for `#[tokio::test]`.
+
+/// Third-party test attributes (last segment of their path), beside the
+/// ones named `test` (e.g. `#[tokio::test]`, `#[test_log::test]`).
+///
+/// The macro tags such tests through their own `#[tag]` only: within
+/// a tagged module, the ones without it are rejected.
+pub const THIRD_PARTY_TEST_ATTRS: [&str; 6] = [
+    "rstest",
+    "test_case",
+    "test_matrix",
+    "quickcheck",
+    "proptest",
+    "wasm_bindgen_test",
+];
+
+/// Check if an attribute is the tag one, i.e. `#[tag]` or `#[pinny::tag]`
+pub fn is_tag_attr<S: AsRef<str>>(segments: &[S]) -> bool {
+    match segments {
+        [ident] => ident.as_ref() == "tag",
+        [first, second] => {
+            first.as_ref() == "pinny" && second.as_ref() == "tag"
+        }
+        _ => false,
+    }
+}
+
+/// Check if an attribute is a test one handled by the macro, that is any
+/// path ending with `test` (e.g. `#[test]`, `#[tokio::test]`).
+///
+/// These are the tests tagged by an enclosing tagged module, and the ones
+/// getting the test-only features (e.g. the tag `ignore`, the gate, the registry).
+pub fn is_test_attr<S: AsRef<str>>(segments: &[S]) -> bool {
+    segments.last().is_some_and(|last| last.as_ref() == "test")
+}
+
+/// Check if an attribute is any test one, that is a test attribute handled
+/// by the macro or a third-party one (see [`THIRD_PARTY_TEST_ATTRS`])
+pub fn is_any_test_attr<S: AsRef<str>>(segments: &[S]) -> bool {
+    is_test_attr(segments)
+        || segments
+            .last()
+            .is_some_and(|last| THIRD_PARTY_TEST_ATTRS.contains(&last.as_ref()))
+}
@@ -0,0 +1,826 @@
+use crate::{expression, mangling};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Default)]
+pub struct Config {
+    pub allowed_tags: Vec<String>,
+    /// Definitions of the tags declared in the `tags` table
+    pub definitions: HashMap<String, TagDefinition>,
+    /// Aliases declared by the tag definitions, mapped to their tag
+    pub aliases: HashMap<String, String>,
+    /// Tag groups, as declared in the `groups` table
+    pub groups: Vec<TagGroup>,
+    /// Pairs of tags that cannot be used together, as declared in `conflicts`
+    pub conflicts: Vec<(String, String)>,
+    /// Files the configuration was read from (e.g. the package manifest)
+    pub sources: Vec<PathBuf>,
+    /// Skip tests at runtime according to `PINNY_INCLUDE`/`PINNY_EXCLUDE`
+    pub runtime_filter: bool,
+    /// Name of the `pinny-runtime` crate within the package (e.g. `pinny_runtime`),
+    /// when among its dependencies
+    pub runtime_crate: Option<String>,
+    /// Compile tests conditionally to their tags, when set
+    pub gate: Option<GateMode>,
+    /// Prefix of the gating cfg or features (`<prefix>_only` and
+    /// `<prefix>_tag_<tag>`), when not the default `pinny`
+    pub gate_prefix: Option<String>,
+    /// Register the tagged tests in a linker section, for `pinny-runtime`
+    pub registry: bool,
+    /// Every test must be tagged (enforced by `cargo pinny untagged`)
+    pub require_tags: bool,
+}
+
+/// Definition of a tag, as declared in `[package.metadata.pinny.tags.<tag>]`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TagDefinition {
+    pub description: Option<String>,
+    /// Tags implied by this tag (applied along with it)
+    pub implies: Vec<String>,
+    /// Alternative names for this tag
+    pub aliases: Vec<String>,
+    /// Deprecation note (e.g. the replacement tag), when the tag is deprecated
+    pub deprecated: Option<String>,
+    /// Reason of the `#[ignore]` applied to tagged tests, when ignored by default
+    pub ignore: Option<String>,
+}
+
+/// Group of tags, as declared in `[package.metadata.pinny.groups.<group>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagGroup {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub rule: GroupRule,
+}
+
+/// Rule a test tags must satisfy for a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupRule {
+    /// At least one tag of the group is required
+    Required,
+    /// Exactly one tag of the group is required
+    ExactlyOne,
+    /// At most one tag of the group is allowed
+    AtMostOne,
+}
+
+impl GroupRule {
+    fn parse(rule: &str) -> Option<Self> {
+        match rule {
+            "required" => Some(Self::Required),
+            "exactly_one" => Some(Self::ExactlyOne),
+            "at_most_one" => Some(Self::AtMostOne),
+            _ => None,
+        }
+    }
+}
+
+/// Default prefix of the gating cfg or features
+pub const DEFAULT_GATE_PREFIX: &str = "pinny";
+
+/// How tests are conditionally compiled according to their tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateMode {
+    /// `--cfg <prefix>_only --cfg <prefix>_tag_<tag>` rustc flags
+    Cfg,
+    /// `<prefix>_only` and `<prefix>_tag_<tag>` crate features
+    Feature,
+}
+
+impl GateMode {
+    fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "cfg" => Some(Self::Cfg),
+            "feature" => Some(Self::Feature),
+            _ => None,
+        }
+    }
+}
+
+impl Config {
+    /// Return the description of a tag, if any
+    #[must_use]
+    pub fn description(&self, tag: &str) -> Option<&str> {
+        self.definitions.get(tag)?.description.as_deref()
+    }
+
+    /// Check if a tag (or an alias) is allowed
+    #[must_use]
+    pub fn is_allowed(&self, tag: &str) -> bool {
+        self.allowed_tags.iter().any(|allowed| allowed == tag)
+            || self.aliases.contains_key(tag)
+    }
+
+    /// Return the tag an alias refers to, or the tag itself when not an alias
+    pub fn canonical<'a>(&'a self, tag: &'a str) -> &'a str {
+        self.aliases.get(tag).map_or(tag, String::as_str)
+    }
+
+    /// Return the deprecation note of a tag, if deprecated
+    #[must_use]
+    pub fn deprecation(&self, tag: &str) -> Option<&str> {
+        self.definitions.get(tag)?.deprecated.as_deref()
+    }
+
+    /// Return the reason to ignore the tests tagged with a tag, if ignored
+    #[must_use]
+    pub fn ignore_reason(&self, tag: &str) -> Option<&str> {
+        self.definitions.get(tag)?.ignore.as_deref()
+    }
+
+    /// Return the tags directly implied by a tag
+    #[must_use]
+    pub fn implied(&self, tag: &str) -> &[String] {
+        self.definitions
+            .get(tag)
+            .map_or(&[], |definition| definition.implies.as_slice())
+    }
+
+    /// Return the prefix of the gating cfg or features
+    #[must_use]
+    pub fn gate_prefix(&self) -> &str {
+        self.gate_prefix.as_deref().unwrap_or(DEFAULT_GATE_PREFIX)
+    }
+}
+
+/// Name of the standalone config file, looked up next to the manifest
+pub const CONFIG_FILE_NAME: &str = "pinny.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to get CARGO_MANIFEST_DIR: {0}")]
+    MissingEnvVar(#[from] std::env::VarError),
+
+    #[error("Failed to read Cargo.toml: {0}")]
+    ReadError(#[from] std::io::Error),
+
+    #[error("Invalid TOML format: {0}")]
+    ParseError(#[from] toml::de::Error),
+
+    #[error("Missing `allowed` tags in Cargo.toml")]
+    MissingTags,
+
+    #[error("`allowed` tags must be an array")]
+    InvalidArrayFormat,
+
+    #[error("Each tag should be a string")]
+    InvalidStringType,
+
+    #[error("`tags` must be a table")]
+    InvalidTableFormat,
+
+    #[error("Invalid definition for tag: {0}")]
+    InvalidTagDefinition(String),
+
+    #[error("Duplicated tag found: {0}")]
+    DuplicateTag(String),
+
+    #[error("Tag is not a valid rust identifier: {0}")]
+    InvalidTagFormat(String),
+
+    #[error("Tag is reserved as tags delimiter or operator: {0}")]
+    ReservedTag(String),
+
+    #[error("Unknown tag referenced: {0}")]
+    UnknownTag(String),
+
+    #[error("Cycle detected in implied tags: {0}")]
+    ImpliesCycle(String),
+
+    #[error("Invalid definition for group: {0}")]
+    InvalidGroupDefinition(String),
+
+    #[error("Duplicated group found: {0}")]
+    DuplicateGroup(String),
+
+    #[error("Each conflict should be a pair of tags: {0}")]
+    InvalidConflict(String),
+
+    #[error("Invalid value for setting: {0}")]
+    InvalidSetting(String),
+
+    #[error("`allowed.workspace` must be `true` to inherit tags")]
+    InvalidWorkspaceInheritance,
+
+    #[error("Workspace manifest not found for tags inheritance")]
+    MissingWorkspace,
+
+    #[error("`config` must be a path to a pinny config file")]
+    InvalidConfigPath,
+
+    #[error("Tags are declared in both the manifest metadata and {}, with different settings", .0.display())]
+    ConflictingConfig(PathBuf),
+
+    #[error("{source} (in {})", path.display())]
+    Manifest {
+        path: PathBuf,
+        source: Box<ConfigError>,
+    },
+}
+
+impl ConfigError {
+    /// Attach the manifest the error has been produced from.
+    ///
+    /// Errors already carrying a manifest are left untouched,
+    /// so that the innermost (most accurate) manifest is reported.
+    fn in_manifest(self, path: &Path) -> Self {
+        match self {
+            Self::Manifest { .. } | Self::MissingEnvVar(_) => self,
+            _ => Self::Manifest {
+                path: path.to_path_buf(),
+                source: Box::new(self),
+            },
+        }
+    }
+}
+
+impl PartialEq for ConfigError {
+    fn eq(&self, other: &Self) -> bool {
+        use ConfigError::{
+            ConflictingConfig, DuplicateGroup, DuplicateTag, ImpliesCycle,
+            InvalidArrayFormat, InvalidConfigPath, InvalidConflict,
+            InvalidGroupDefinition, InvalidSetting, InvalidStringType,
+            InvalidTableFormat, InvalidTagDefinition, InvalidTagFormat,
+            InvalidWorkspaceInheritance, Manifest, MissingEnvVar, MissingTags,
+            MissingWorkspace, ParseError, ReadError, ReservedTag, UnknownTag,
+        };
+        match (self, other) {
+            (MissingEnvVar(_), MissingEnvVar(_))
+            | (ReadError(_), ReadError(_))
+            | (ParseError(_), ParseError(_))
+            | (MissingTags, MissingTags)
+            | (InvalidArrayFormat, InvalidArrayFormat)
+            | (InvalidStringType, InvalidStringType)
+            | (InvalidTableFormat, InvalidTableFormat)
+            | (InvalidWorkspaceInheritance, InvalidWorkspaceInheritance)
+            | (MissingWorkspace, MissingWorkspace)
+            | (InvalidConfigPath, InvalidConfigPath) => true,
+            (ConflictingConfig(p1), ConflictingConfig(p2)) => p1 == p2,
+            (DuplicateTag(s1), DuplicateTag(s2))
+            | (InvalidTagFormat(s1), InvalidTagFormat(s2))
+            | (ReservedTag(s1), ReservedTag(s2))
+            | (InvalidTagDefinition(s1), InvalidTagDefinition(s2))
+            | (UnknownTag(s1), UnknownTag(s2))
+            | (ImpliesCycle(s1), ImpliesCycle(s2))
+            | (InvalidGroupDefinition(s1), InvalidGroupDefinition(s2))
+            | (DuplicateGroup(s1), DuplicateGroup(s2))
+            | (InvalidConflict(s1), InvalidConflict(s2))
+            | (InvalidSetting(s1), InvalidSetting(s2)) => s1 == s2,
+            (
+                Manifest {
+                    path: p1,
+                    source: e1,
+                },
+                Manifest {
+                    path: p2,
+                    source: e2,
+                },
+            ) => p1 == p2 && e1 == e2,
+            _ => false,
+        }
+    }
+}
+
+pub struct ConfigFactory {
+    _prevent_instance: (),
+}
+
+impl ConfigFactory {
+    /// Build the configuration of the package being compiled
+    /// (i.e. the one in `CARGO_MANIFEST_DIR`)
+    ///
+    /// # Errors
+    ///
+    /// Return an error when the manifest directory is unknown,
+    /// or the configuration cannot be read or is not valid.
+    pub fn create() -> Result<Config, ConfigError> {
+        // Introduced `PINNY_CARGO_MANIFEST_DIR` as on override for `CARGO_MANIFEST_DIR` to execute tests with trybuilder crate
+        // This workaroubnd is suggested even here https://github.com/dtolnay/trybuild/issues/202
+        let manifest_dir = std::env::var("PINNY_CARGO_MANIFEST_DIR")
+            .or_else(|_| std::env::var("CARGO_MANIFEST_DIR"))?;
+        Self::from_manifest_dir(manifest_dir)
+    }
+
+    /// Build the configuration of the package in the given directory
+    ///
+    /// # Errors
+    ///
+    /// Return an error when the configuration cannot be read or is not valid.
+    pub fn from_manifest_dir(
+        manifest_dir: impl AsRef<Path>,
+    ) -> Result<Config, ConfigError> {
+        Self::from_manifest(&manifest_dir.as_ref().join("Cargo.toml"))
+    }
+
+    /// Build the configuration from a package manifest.
+    ///
+    /// Tags are read from `package.metadata.pinny`, either as:
+    /// - `allowed`: an array of tags
+    /// - `tags`: a table of tag definitions (e.g. `tags.<tag>.description`)
+    ///
+    /// `allowed` can also be `{ workspace = true, extend = [...] }` to inherit
+    /// the tags in `workspace.metadata.pinny` from the workspace root manifest,
+    /// adding the package specific tags on top.
+    ///
+    /// The same settings can be provided by a standalone config file instead,
+    /// see [`find_config_file`] for its location.
+    fn from_manifest(cargo_path: &Path) -> Result<Config, ConfigError> {
+        let cargo_toml = read_manifest(cargo_path)?;
+
+        let config_file = find_config_file(cargo_path, &cargo_toml)
+            .map_err(|e| e.in_manifest(cargo_path))?;
+        let metadata = pinny_metadata(&cargo_toml, "package");
+        let (pinny_path, pinny) = if let Some(file_path) = config_file {
+            let file_toml = read_manifest(&file_path)?;
+            if metadata.is_some_and(|meta| !same_settings(meta, &file_toml)) {
+                return Err(ConfigError::ConflictingConfig(file_path)
+                    .in_manifest(cargo_path));
+            }
+            if !declares_tags(&file_toml) {
+                return Err(ConfigError::MissingTags.in_manifest(&file_path));
+            }
+            (file_path, file_toml)
+        } else {
+            let pinny = metadata
+                .ok_or(ConfigError::MissingTags)
+                .map_err(|e| e.in_manifest(cargo_path))?;
+            (cargo_path.to_path_buf(), pinny.clone())
+        };
+        let pinny = &pinny;
+        let pinny_path = pinny_path.as_path();
+
+        let mut config = Config {
+            sources: vec![cargo_path.to_path_buf()],
+            runtime_crate: runtime_crate(&cargo_toml),
+            ..Config::default()
+        };
+        if pinny_path != cargo_path {
+            config.sources.push(pinny_path.to_path_buf());
+        }
+        match pinny.get("allowed") {
+            Some(toml::Value::Table(inherit)) => {
+                if inherit.get("workspace").and_then(toml::Value::as_bool)
+                    != Some(true)
+                {
+                    return Err(ConfigError::InvalidWorkspaceInheritance
+                        .in_manifest(pinny_path));
+                }
+
+                let workspace_path =
+                    find_workspace_manifest(cargo_path, &cargo_toml)
+                        .map_err(|e| e.in_manifest(cargo_path))?;
+                let workspace_toml = read_manifest(&workspace_path)?;
+                if workspace_path != cargo_path {
+                    config.sources.push(workspace_path.clone());
+                }
+                pinny_metadata(&workspace_toml, "workspace")
+                    .ok_or(ConfigError::MissingTags)
+                    .and_then(|pinny| load_tags(&mut config, pinny))
+                    .and_then(|()| validate_references(&config))
+                    .map_err(|e| e.in_manifest(&workspace_path))?;
+                debug!(
+                    "Inherited tags from {}: {:?}",
+                    workspace_path.display(),
+                    config.allowed_tags
+                );
+
+                if let Some(extend) = inherit.get("extend") {
+                    parse_tags(extend)
+                        .and_then(|tags| add_tags(&mut config, tags))
+                        .map_err(|e| e.in_manifest(pinny_path))?;
+                }
+                load_definitions(&mut config, pinny)
+                    .map_err(|e| e.in_manifest(pinny_path))?;
+            }
+            _ => load_tags(&mut config, pinny)
+                .map_err(|e| e.in_manifest(pinny_path))?,
+        }
+        validate_references(&config).map_err(|e| e.in_manifest(pinny_path))?;
+        Ok(config)
+    }
+}
+
+/// Name of the `pinny-runtime` crate among the (dev-)dependencies of a package,
+/// for any target, as imported (e.g. `pinny_runtime`, or the name
+/// of a renamed dependency)
+fn runtime_crate(cargo_toml: &toml::Value) -> Option<String> {
+    const RUNTIME_PACKAGE: &str = "pinny-runtime";
+    const KINDS: [&str; 2] = ["dependencies", "dev-dependencies"];
+
+    let targets = cargo_toml
+        .get("target")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values());
+    std::iter::once(cargo_toml)
+        .chain(targets)
+        .flat_map(|root| KINDS.iter().filter_map(|kind| root.get(kind)))
+        .filter_map(toml::Value::as_table)
+        .flatten()
+        .find(|(name, dependency)| {
+            dependency
+                .get("package")
+                .and_then(toml::Value::as_str)
+                .unwrap_or(name)
+                == RUNTIME_PACKAGE
+        })
+        .map(|(name, _)| name.replace('-', "_"))
+}
+
+/// Check if a gate prefix makes valid cfg and feature names, as an identifier
+fn is_valid_gate_prefix(prefix: &str) -> bool {
+    let mut chars = prefix.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Get the `<root>.metadata.pinny` table, if it declares any tag
+fn pinny_metadata<'a>(
+    manifest: &'a toml::Value,
+    root: &str,
+) -> Option<&'a toml::Value> {
+    manifest
+        .get(root)
+        .and_then(|root| root.get("metadata"))
+        .and_then(|meta| meta.get("pinny"))
+        .filter(|pinny| declares_tags(pinny))
+}
+
+/// Check if a pinny table declares any tag
+fn declares_tags(pinny: &toml::Value) -> bool {
+    pinny.get("allowed").is_some() || pinny.get("tags").is_some()
+}
+
+/// Locate the standalone config file of a package, if any.
+///
+/// The path in `package.metadata.pinny.config` (relative to the manifest)
+/// takes precedence over a `pinny.toml` file next to the manifest.
+fn find_config_file(
+    cargo_path: &Path,
+    cargo_toml: &toml::Value,
+) -> Result<Option<PathBuf>, ConfigError> {
+    let package_dir = cargo_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let explicit = cargo_toml
+        .get("package")
+        .and_then(|pkg| pkg.get("metadata"))
+        .and_then(|meta| meta.get("pinny"))
+        .and_then(|pinny| pinny.get("config"));
+    if let Some(explicit) = explicit {
+        return explicit
+            .as_str()
+            .map(|path| Some(package_dir.join(path)))
+            .ok_or(ConfigError::InvalidConfigPath);
+    }
+
+    let adjacent = package_dir.join(CONFIG_FILE_NAME);
+    Ok(adjacent.is_file().then_some(adjacent))
+}
+
+/// Check if the metadata and the config file declare the same settings
+/// (the `config` key of the metadata aside)
+fn same_settings(metadata: &toml::Value, file: &toml::Value) -> bool {
+    fn settings(pinny: &toml::Value) -> Option<Vec<(&String, &toml::Value)>> {
+        pinny.as_table().map(|table| {
+            table.iter().filter(|(key, _)| *key != "config").collect()
+        })
+    }
+    settings(metadata) == settings(file)
+}
+
+/// Load tags from a pinny table (`allowed` array and definitions)
+fn load_tags(
+    config: &mut Config,
+    pinny: &toml::Value,
+) -> Result<(), ConfigError> {
+    if let Some(allowed) = pinny.get("allowed") {
+        add_tags(config, parse_tags(allowed)?)?;
+    }
+    load_definitions(config, pinny)
+}
+
+/// Load definitions from a pinny table (`tags`, `groups` and `conflicts`)
+/// along with the settings (`runtime_filter`, `gate`, `gate_prefix`, `registry`
+/// and `require_tags`)
+fn load_definitions(
+    config: &mut Config,
+    pinny: &toml::Value,
+) -> Result<(), ConfigError> {
+    if let Some(runtime_filter) = pinny.get("runtime_filter") {
+        config.runtime_filter = runtime_filter.as_bool().ok_or_else(|| {
+            ConfigError::InvalidSetting("runtime_filter".into())
+        })?;
+    }
+    if let Some(registry) = pinny.get("registry") {
+        config.registry = registry
+            .as_bool()
+            .ok_or_else(|| ConfigError::InvalidSetting("registry".into()))?;
+    }
+    if let Some(require_tags) = pinny.get("require_tags") {
+        config.require_tags = require_tags.as_bool().ok_or_else(|| {
+            ConfigError::InvalidSetting("require_tags".into())
+        })?;
+    }
+    if let Some(gate) = pinny.get("gate") {
+        config.gate = Some(
+            gate.as_str()
+                .and_then(GateMode::parse)
+                .ok_or_else(|| ConfigError::InvalidSetting("gate".into()))?,
+        );
+    }
+    if let Some(prefix) = pinny.get("gate_prefix") {
+        config.gate_prefix = Some(
+            prefix
+                .as_str()
+                .filter(|prefix| is_valid_gate_prefix(prefix))
+                .ok_or_else(|| {
+                    ConfigError::InvalidSetting("gate_prefix".into())
+                })?
+                .to_string(),
+        );
+    }
+    if let Some(definitions) = pinny.get("tags") {
+        add_definitions(config, definitions)?;
+    }
+    if let Some(groups) = pinny.get("groups") {
+        add_groups(config, groups)?;
+    }
+    if let Some(conflicts) = pinny.get("conflicts") {
+        add_conflicts(config, conflicts)?;
+    }
+    Ok(())
+}
+
+/// Add conflicting tags from a `conflicts` array of pairs, such as:
+/// `[["unit", "requires_network"], ...]`
+fn add_conflicts(
+    config: &mut Config,
+    conflicts: &toml::Value,
+) -> Result<(), ConfigError> {
+    let conflicts = conflicts
+        .as_array()
+        .ok_or(ConfigError::InvalidArrayFormat)?;
+    for conflict in conflicts {
+        let invalid = || ConfigError::InvalidConflict(conflict.to_string());
+        let pair = parse_tags(conflict).map_err(|_| invalid())?;
+        let pair = <[String; 2]>::try_from(pair).map_err(|_| invalid())?;
+        if pair[0] == pair[1] {
+            return Err(invalid());
+        }
+        config.conflicts.push(pair.into());
+    }
+    Ok(())
+}
+
+/// Add tag groups from a `groups` table, such as:
+/// `<group> = { tags = [...], rule = "required" | "exactly_one" | "at_most_one" }`
+fn add_groups(
+    config: &mut Config,
+    groups: &toml::Value,
+) -> Result<(), ConfigError> {
+    let groups = groups.as_table().ok_or(ConfigError::InvalidTableFormat)?;
+    for (name, group) in groups {
+        let invalid = || ConfigError::InvalidGroupDefinition(name.clone());
+        if config.groups.iter().any(|group| group.name == *name) {
+            return Err(ConfigError::DuplicateGroup(name.clone()));
+        }
+        let tags = group
+            .get("tags")
+            .ok_or_else(invalid)
+            .and_then(|tags| parse_tags(tags).map_err(|_| invalid()))?;
+        let rule = group
+            .get("rule")
+            .and_then(toml::Value::as_str)
+            .and_then(GroupRule::parse)
+            .ok_or_else(invalid)?;
+        config.groups.push(TagGroup {
+            name: name.clone(),
+            tags,
+            rule,
+        });
+    }
+    Ok(())
+}
+
+/// Add tag definitions from a `tags` table.
+///
+/// A definition either describes an already allowed tag or declares a new one.
+/// A tag can be defined only once.
+fn add_definitions(
+    config: &mut Config,
+    definitions: &toml::Value,
+) -> Result<(), ConfigError> {
+    let definitions = definitions
+        .as_table()
+        .ok_or(ConfigError::InvalidTableFormat)?;
+    for (tag, definition) in definitions {
+        let definition = parse_definition(tag, definition)?;
+        if config.definitions.contains_key(tag) {
+            return Err(ConfigError::DuplicateTag(tag.clone()));
+        }
+        if !config.allowed_tags.contains(tag) {
+            add_tags(config, vec![tag.clone()])?;
+        }
+        let aliases =
+            validate_tags(&known_names(config), definition.aliases.clone())?;
+        for alias in aliases {
+            config.aliases.insert(alias, tag.clone());
+        }
+        config.definitions.insert(tag.clone(), definition);
+    }
+    Ok(())
+}
+
+/// Parse a tag definition, such as:
+/// `{ description = "...", implies = [...], aliases = [...], deprecated = "..." }`
+///
+/// The tagged tests can also be ignored by default, with `ignore = true`
+/// or with a reason: `ignore = "..."`
+fn parse_definition(
+    tag: &str,
+    definition: &toml::Value,
+) -> Result<TagDefinition, ConfigError> {
+    let invalid = || ConfigError::InvalidTagDefinition(tag.to_string());
+    let definition = definition.as_table().ok_or_else(invalid)?;
+    let text = |key: &str| {
+        definition
+            .get(key)
+            .map(|text| text.as_str().map(str::to_string).ok_or_else(invalid))
+            .transpose()
+    };
+    let tag_list = |key: &str| {
+        definition
+            .get(key)
+            .map_or_else(|| Ok(Vec::new()), parse_tags)
+            .map_err(|_| invalid())
+    };
+    let ignore = match definition.get("ignore") {
+        None | Some(toml::Value::Boolean(false)) => None,
+        Some(toml::Value::Boolean(true)) => {
+            Some(format!("pinny: tagged {tag}"))
+        }
+        Some(toml::Value::String(reason)) => {
+            Some(format!("pinny: tagged {tag}, {reason}"))
+        }
+        Some(_) => return Err(invalid()),
+    };
+    Ok(TagDefinition {
+        description: text("description")?,
+        implies: tag_list("implies")?,
+        aliases: tag_list("aliases")?,
+        deprecated: text("deprecated")?,
+        ignore,
+    })
+}
+
+/// Validate and add tags to the allowed ones
+fn add_tags(config: &mut Config, tags: Vec<String>) -> Result<(), ConfigError> {
+    let tags = validate_tags(&known_names(config), tags)?;
+    config.allowed_tags.extend(tags);
+    Ok(())
+}
+
+/// Return all the names in use, that is allowed tags and aliases
+fn known_names(config: &Config) -> Vec<String> {
+    config
+        .allowed_tags
+        .iter()
+        .chain(config.aliases.keys())
+        .cloned()
+        .collect()
+}
+
+/// Check that tags referenced by definitions (implied tags, group tags, conflicts)
+/// are allowed, and that implied tags are free of cycles
+/// (e.g. `a` implies `b` which implies `a`)
+fn validate_references(config: &Config) -> Result<(), ConfigError> {
+    fn visit<'a>(
+        config: &'a Config,
+        tag: &'a str,
+        path: &mut Vec<&'a str>,
+        visited: &mut HashSet<&'a str>,
+    ) -> Result<(), ConfigError> {
+        if let Some(start) = path.iter().position(|each| *each == tag) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(tag);
+            return Err(ConfigError::ImpliesCycle(cycle.join(" -> ")));
+        }
+        if !visited.insert(tag) {
+            return Ok(());
+        }
+        path.push(tag);
+        for implied in config.implied(tag) {
+            if !config.allowed_tags.contains(implied) {
+                return Err(ConfigError::UnknownTag(implied.clone()));
+            }
+            visit(config, implied, path, visited)?;
+        }
+        path.pop();
+        Ok(())
+    }
+
+    let mut visited = HashSet::new();
+    for tag in &config.allowed_tags {
+        visit(config, tag, &mut Vec::new(), &mut visited)?;
+    }
+
+    for group in &config.groups {
+        if let Some(unknown) = group
+            .tags
+            .iter()
+            .find(|tag| !config.allowed_tags.contains(tag))
+        {
+            return Err(ConfigError::UnknownTag(unknown.clone()));
+        }
+    }
+
+    for (first, second) in &config.conflicts {
+        for tag in [first, second] {
+            if !config.allowed_tags.contains(tag) {
+                return Err(ConfigError::UnknownTag(tag.clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read and parse a manifest, reporting its path on failure.
+fn read_manifest(cargo_path: &Path) -> Result<toml::Value, ConfigError> {
+    let read = || -> Result<toml::Value, ConfigError> {
+        let cargo_string = std::fs::read_to_string(cargo_path)?;
+        Ok(cargo_string.parse()?)
+    };
+    read().map_err(|e| e.in_manifest(cargo_path))
+}
+
+/// Locate the workspace root manifest of a package.
+///
+/// Honor the `package.workspace` key when present, otherwise look for
+/// the closest manifest declaring a `[workspace]` (the package manifest included).
+fn find_workspace_manifest(
+    cargo_path: &Path,
+    cargo_toml: &toml::Value,
+) -> Result<PathBuf, ConfigError> {
+    let package_dir = cargo_path.parent().unwrap_or_else(|| Path::new(""));
+
+    if let Some(ws_dir) = cargo_toml
+        .get("package")
+        .and_then(|pkg| pkg.get("workspace"))
+        .and_then(toml::Value::as_str)
+    {
+        return Ok(package_dir.join(ws_dir).join("Cargo.toml"));
+    }
+
+    if cargo_toml.get("workspace").is_some() {
+        return Ok(cargo_path.to_path_buf());
+    }
+
+    for dir in package_dir.ancestors().skip(1) {
+        let candidate = dir.join("Cargo.toml");
+        if candidate.is_file()
+            && read_manifest(&candidate)?.get("workspace").is_some()
+        {
+            return Ok(candidate);
+        }
+    }
+    Err(ConfigError::MissingWorkspace)
+}
+
+/// Parse an array of tags
+fn parse_tags(value: &toml::Value) -> Result<Vec<String>, ConfigError> {
+    value
+        .as_array()
+        .ok_or(ConfigError::InvalidArrayFormat)?
+        .iter()
+        .map(|mode| {
+            mode.as_str()
+                .map(std::string::ToString::to_string)
+                .ok_or(ConfigError::InvalidStringType)
+        })
+        .collect()
+}
+
+/// Check tags for duplication (even against already `known` tags),
+/// reserved names and format (as `mangling::is_valid_tag`)
+fn validate_tags(
+    known: &[String],
+    tags: Vec<String>,
+) -> Result<Vec<String>, ConfigError> {
+    let mut seen: HashSet<&String> = known.iter().collect();
+    for tag in &tags {
+        if !seen.insert(tag) {
+            return Err(ConfigError::DuplicateTag(tag.clone()));
+        }
+        if tag == mangling::DELIMITER || expression::OPERATORS.contains(&&**tag)
+        {
+            return Err(ConfigError::ReservedTag(tag.clone()));
+        }
+        if !mangling::is_valid_tag(tag) {
+            return Err(ConfigError::InvalidTagFormat(tag.clone()));
+        }
+    }
+    Ok(tags)
+}
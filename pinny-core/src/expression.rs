@@ -0,0 +1,386 @@
+//! Boolean expressions over tags, e.g. `bitcoin and not (slow or flaky)`.
+//!
+//! Tags are combined with `and`, `or`, `not` and parentheses, where `not`
+//! binds tighter than `and`, which binds tighter than `or` (the same syntax
+//! as the `PINNY_INCLUDE`/`PINNY_EXCLUDE` runtime filters).
+//!
+//! An [`Expr`] can be evaluated against the tags of a test, or compiled to
+//! the filters of the test runners:
+//! - [`Expr::to_nextest`]: a nextest filterset (e.g. `cargo nextest run -E <filterset>`)
+//! - [`Expr::libtest_filters`]: the exact names of the matching tests
+//!   (e.g. `cargo test -- --exact <names>`)
+//!
+//! Both only match the tags between the [`DELIMITER`] segments of the test names,
+//! so that a tag never matches a module or a function named the same.
+use crate::config::Config;
+use crate::mangling::{self, DELIMITER, SEPARATOR};
+use crate::validation;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Operators of the expressions, reserved and so never valid tags
+pub const OPERATORS: [&str; 3] = ["and", "or", "not"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExpressionError {
+    #[error("Empty tag expression")]
+    Empty,
+
+    #[error("Unexpected character '{0}' at position {1}")]
+    UnexpectedChar(char, usize),
+
+    #[error("Unexpected '{0}' at position {1}")]
+    UnexpectedToken(String, usize),
+
+    #[error("Unexpected end of expression, expected {0}")]
+    UnexpectedEnd(&'static str),
+
+    #[error("Tag is not a valid tag name: {0}")]
+    InvalidTag(String),
+
+    #[error("{0}")]
+    NotAllowed(String),
+}
+
+/// Boolean expression over tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Tag(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parse an expression, e.g. `bitcoin and not (slow or flaky)`
+    ///
+    /// # Errors
+    ///
+    /// Return an error when the expression is malformed,
+    /// or refers to a name that cannot be a tag.
+    pub fn parse(input: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(ExpressionError::Empty);
+        }
+        let mut parser = Parser { tokens, index: 0 };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.index) {
+            Some((token, position)) => Err(ExpressionError::UnexpectedToken(
+                token.to_string(),
+                *position,
+            )),
+            None => Ok(expr),
+        }
+    }
+
+    /// Check if the expression holds for the tags of a test
+    pub fn matches<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        match self {
+            Self::Tag(tag) => tags.iter().any(|each| each.as_ref() == tag),
+            Self::Not(expr) => !expr.matches(tags),
+            Self::And(left, right) => left.matches(tags) && right.matches(tags),
+            Self::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+
+    /// Tags referred by the expression, in order of appearance
+    #[must_use]
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags = Vec::new();
+        self.visit_tags(&mut |tag| {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        });
+        tags
+    }
+
+    /// Check the tags of the expression against the configuration,
+    /// replacing the aliases with their tag (as done for the test names).
+    ///
+    /// # Errors
+    ///
+    /// Return an error for the first tag not allowed by the configuration.
+    pub fn resolve(self, config: &Config) -> Result<Self, ExpressionError> {
+        let resolve_box = |expr: Box<Self>| expr.resolve(config).map(Box::new);
+        Ok(match self {
+            Self::Tag(tag) if config.is_allowed(&tag) => {
+                Self::Tag(config.canonical(&tag).to_string())
+            }
+            Self::Tag(tag) => {
+                return Err(ExpressionError::NotAllowed(
+                    validation::invalid_tag_message(&tag, config),
+                ))
+            }
+            Self::Not(expr) => Self::Not(resolve_box(expr)?),
+            Self::And(left, right) => {
+                Self::And(resolve_box(left)?, resolve_box(right)?)
+            }
+            Self::Or(left, right) => {
+                Self::Or(resolve_box(left)?, resolve_box(right)?)
+            }
+        })
+    }
+
+    /// Compile the expression to a nextest filterset, e.g.
+    /// `test(/(?:^|::)t::(?:<tag>::)*slow::(?:<tag>::)*t$/)` for `slow`
+    /// (where `<tag>` stands for any tag).
+    ///
+    /// As for [`Expr::libtest_filters`], untagged tests never match: an
+    /// expression holding for no tag at all (e.g. `not slow`) is restricted
+    /// to the tagged tests.
+    #[must_use]
+    pub fn to_nextest(&self) -> String {
+        let mut output = String::new();
+        if self.matches::<&str>(&[]) {
+            output = format!("test(/{}/) and ", tagged_regex());
+            if matches!(self, Self::Or(..)) {
+                output.push('(');
+                self.render_nextest(&mut output);
+                output.push(')');
+                return output;
+            }
+        }
+        self.render_nextest(&mut output);
+        output
+    }
+
+    fn render_nextest(&self, output: &mut String) {
+        self.render(output, &|tag| format!("test(/{}/)", tag_regex(tag)));
+    }
+
+    /// Select the names of the tests (as reported by the test harness)
+    /// matching the expression, to be used as exact libtest filters
+    /// (e.g. `cargo test -- --exact <names>`).
+    ///
+    /// libtest filters only match plain substrings, which cannot tell
+    /// a tag from a module named the same, hence the exact names.
+    /// Untagged tests never match.
+    pub fn libtest_filters<'a>(
+        &self,
+        test_names: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<&'a str> {
+        test_names
+            .into_iter()
+            .filter(|name| {
+                mangling::decode(name)
+                    .is_some_and(|test| self.matches(&test.tags))
+            })
+            .collect()
+    }
+
+    fn visit_tags<'a>(&'a self, visitor: &mut impl FnMut(&'a str)) {
+        match self {
+            Self::Tag(tag) => visitor(tag),
+            Self::Not(expr) => expr.visit_tags(visitor),
+            Self::And(left, right) | Self::Or(left, right) => {
+                left.visit_tags(visitor);
+                right.visit_tags(visitor);
+            }
+        }
+    }
+
+    /// Render the expression, with the given rendering of the tags.
+    ///
+    /// Operands combining different operators are always parenthesized,
+    /// so that the output doesn't rely on the precedence of the target syntax.
+    fn render(&self, output: &mut String, tag: &dyn Fn(&str) -> String) {
+        let operand = |output: &mut String, expr: &Self, parent: &Self| {
+            let grouped = match expr {
+                Self::Tag(_) | Self::Not(_) => false,
+                Self::And(..) => !matches!(parent, Self::And(..)),
+                Self::Or(..) => !matches!(parent, Self::Or(..)),
+            };
+            if grouped {
+                output.push('(');
+                expr.render(output, tag);
+                output.push(')');
+            } else {
+                expr.render(output, tag);
+            }
+        };
+
+        match self {
+            Self::Tag(name) => output.push_str(&tag(name)),
+            Self::Not(expr) => {
+                output.push_str("not ");
+                if matches!(**expr, Self::Tag(_) | Self::Not(_)) {
+                    expr.render(output, tag);
+                } else {
+                    output.push('(');
+                    expr.render(output, tag);
+                    output.push(')');
+                }
+            }
+            Self::And(left, right) | Self::Or(left, right) => {
+                let operator = if matches!(self, Self::And(..)) {
+                    " and "
+                } else {
+                    " or "
+                };
+                operand(output, left, self);
+                output.push_str(operator);
+                operand(output, right, self);
+            }
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExpressionError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = String::new();
+        self.render(&mut output, &ToString::to_string);
+        f.write_str(&output)
+    }
+}
+
+/// Regex matching any tag but the delimiter
+const ANY_TAG: &str = "(?:[a-su-z][a-z0-9_]*|t[a-z0-9_]+)";
+
+/// Regex matching the test names having a tag, between the delimiters.
+///
+/// The segments around the tag are any tag but the delimiter, so that the
+/// match can't extend over modules or functions named as the delimiter.
+fn tag_regex(tag: &str) -> String {
+    format!(
+        "(?:^|{SEPARATOR}){DELIMITER}{SEPARATOR}(?:{ANY_TAG}{SEPARATOR})*{tag}{SEPARATOR}(?:{ANY_TAG}{SEPARATOR})*{DELIMITER}$"
+    )
+}
+
+/// Regex matching the tagged test names, that is having at least one tag
+/// between the delimiters, after the test name
+fn tagged_regex() -> String {
+    format!("{SEPARATOR}{DELIMITER}{SEPARATOR}(?:{ANY_TAG}{SEPARATOR})+{DELIMITER}$")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Tag(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag) => f.write_str(tag),
+            Self::And => f.write_str("and"),
+            Self::Or => f.write_str("or"),
+            Self::Not => f.write_str("not"),
+            Self::Open => f.write_str("("),
+            Self::Close => f.write_str(")"),
+        }
+    }
+}
+
+/// Split the expression into tokens, along with their (byte) position
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_whitespace() => continue,
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = position + c.len_utf8();
+                while let Some((next, c)) =
+                    chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+                {
+                    end = next + c.len_utf8();
+                }
+                match &input[position..end] {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    tag if mangling::is_valid_tag(tag) => {
+                        Token::Tag(tag.to_string())
+                    }
+                    tag => return Err(ExpressionError::InvalidTag(tag.into())),
+                }
+            }
+            c => return Err(ExpressionError::UnexpectedChar(c, position)),
+        };
+        tokens.push((token, position));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser, one function per precedence level
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<Expr, ExpressionError> {
+        let mut expr = self.and()?;
+        while self.next_if(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ExpressionError> {
+        let mut expr = self.not()?;
+        while self.next_if(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, ExpressionError> {
+        let Some((token, position)) = self.tokens.get(self.index).cloned()
+        else {
+            return Err(ExpressionError::UnexpectedEnd("a tag"));
+        };
+        self.index += 1;
+        match token {
+            Token::Tag(tag) => Ok(Expr::Tag(tag)),
+            Token::Not => Ok(Expr::Not(Box::new(self.not()?))),
+            Token::Open => {
+                let expr = self.or()?;
+                match self.tokens.get(self.index) {
+                    Some((Token::Close, _)) => {
+                        self.index += 1;
+                        Ok(expr)
+                    }
+                    Some((token, position)) => {
+                        Err(ExpressionError::UnexpectedToken(
+                            token.to_string(),
+                            *position,
+                        ))
+                    }
+                    None => Err(ExpressionError::UnexpectedEnd("')'")),
+                }
+            }
+            Token::And | Token::Or | Token::Close => Err(
+                ExpressionError::UnexpectedToken(token.to_string(), position),
+            ),
+        }
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        let found = self
+            .tokens
+            .get(self.index)
+            .is_some_and(|(token, _)| token == expected);
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+}
@@ -0,0 +1,32 @@
+//! # Pinny Core
+//!
+//! Rules of [pinny](https://crates.io/crates/pinny), as a regular library
+//! shared by the `#[tag]` macro and the tools around it (e.g. test runners and build scripts):
+//!
+//! - [`config`]: parsing and validation of the pinny configuration (e.g. `package.metadata.pinny`)
+//! - [`validation`]: validation of the tags applied to a test, against the configuration
+//! - [`mangling`]: encoding and decoding of the tagged test names
+//! - [`attributes`]: the tag and test attributes recognized by the macro
+//! - [`expression`]: boolean expressions over tags, compiled to the filters of the test runners
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use pinny_core::{config::ConfigFactory, mangling, validation};
+//!
+//! let config = ConfigFactory::from_manifest_dir("path/to/package").unwrap();
+//! assert!(validation::check_labels(&["slow"], &config).is_empty());
+//!
+//! let test = mangling::decode("tests::test_hello::t::slow::t").unwrap();
+//! assert_eq!("test_hello", test.name);
+//! ```
+#[macro_use]
+mod macros;
+
+pub mod attributes;
+pub mod config;
+pub mod expression;
+pub mod mangling;
+#[cfg(test)]
+mod tests;
+pub mod validation;
@@ -0,0 +1,7 @@
+// tracing with debug level
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "debug")]
+        eprintln!("[PINNY][DEBUG][PID: {}] {}", std::process::id(), format_args!($($arg)*));
+    };
+}
@@ -0,0 +1,122 @@
+//! Encoding and decoding of the tagged test names.
+//!
+//! A tagged test is renamed so that its tags become part of its path,
+//! enclosed between [`DELIMITER`] segments:
+//!
+//! ```text
+//! <module_path>::<test_name>::t::<tag1>::<tag2>::t
+//! ```
+
+/// Delimiter of the tags within a tagged test path
+pub const DELIMITER: &str = "t";
+
+/// Separator of the test path segments
+pub const SEPARATOR: &str = "::";
+
+/// A tagged test, as decoded from its path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedTest {
+    /// Path of the module containing the test (empty at the crate root)
+    pub module_path: String,
+    /// Original name of the test function
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+impl TaggedTest {
+    /// Original path of the test, without the tags (e.g. `tests::test_hello`)
+    #[must_use]
+    pub fn path(&self) -> String {
+        if self.module_path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}{SEPARATOR}{}", self.module_path, self.name)
+        }
+    }
+
+    /// Encode the test path, as reported by the test harness
+    #[must_use]
+    pub fn encode(&self) -> String {
+        encode(&self.module_path, &self.name, &self.tags)
+    }
+}
+
+/// Encode the path of a tagged test, e.g. `tests::test_hello::t::tag1::t`
+pub fn encode<S: AsRef<str>>(
+    module_path: &str,
+    name: &str,
+    tags: &[S],
+) -> String {
+    let mut segments: Vec<&str> = Vec::with_capacity(tags.len() + 4);
+    if !module_path.is_empty() {
+        segments.push(module_path);
+    }
+    segments.extend([name, DELIMITER]);
+    segments.extend(tags.iter().map(AsRef::as_ref));
+    segments.push(DELIMITER);
+    segments.join(SEPARATOR)
+}
+
+/// Suffix appended to the path of the closures (e.g. the body of an async test)
+pub const CLOSURE: &str = "{{closure}}";
+
+/// Decode the path of a tagged test, e.g. `tests::test_hello::t::tag1::t`
+///
+/// Both the test name reported by the test harness and the path of the test
+/// function (e.g. from `std::any::type_name`, possibly ending with `{{closure}}`
+/// segments as for async tests) are supported.
+///
+/// As a tag can't be named as the delimiter, the tags are the ones between
+/// the last two delimiters, while the modules before them can be named anyhow
+/// (even as the delimiter).
+///
+/// Return `None` when the path is not the one of a tagged test.
+pub fn decode(path: &str) -> Option<TaggedTest> {
+    let mut segments: Vec<&str> = path.split(SEPARATOR).collect();
+    while segments.last() == Some(&CLOSURE) {
+        segments.pop();
+    }
+
+    let (last, rest) = segments.split_last()?;
+    if *last != DELIMITER {
+        return None;
+    }
+    let start = rest.iter().rposition(|segment| *segment == DELIMITER)?;
+    let (name, module_path) = rest[..start].split_last()?;
+    let tags = &rest[start + 1..];
+    if tags.is_empty()
+        || !tags.iter().all(|tag| is_valid_tag(tag))
+        || !is_identifier(name)
+        || !module_path.iter().all(|module| is_identifier(module))
+    {
+        return None;
+    }
+
+    Some(TaggedTest {
+        module_path: module_path.join(SEPARATOR),
+        name: (*name).to_string(),
+        tags: tags.iter().map(ToString::to_string).collect(),
+    })
+}
+
+/// Check if a name is a valid tag: a lowercase identifier
+/// (e.g. `slow`, `requires_network`), other than the delimiter
+#[must_use]
+pub fn is_valid_tag(tag: &str) -> bool {
+    let mut chars = tag.chars();
+    tag != DELIMITER
+        && chars.next().is_some_and(|first| first.is_ascii_lowercase())
+        && chars
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Check if a path segment looks like a rust identifier (raw ones included)
+fn is_identifier(segment: &str) -> bool {
+    let segment = segment.strip_prefix("r#").unwrap_or(segment);
+    let mut chars = segment.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && segment != "_"
+}
@@ -0,0 +1,33 @@
+use crate::attributes::*;
+
+#[test]
+fn test_is_tag_attr() {
+    assert!(is_tag_attr(&["tag"]));
+    assert!(is_tag_attr(&["pinny", "tag"]));
+    assert!(!is_tag_attr(&["other", "tag"]));
+    assert!(!is_tag_attr(&["pinny", "tag", "tag"]));
+    assert!(!is_tag_attr(&["test"]));
+    assert!(!is_tag_attr::<&str>(&[]));
+}
+
+#[test]
+fn test_is_test_attr() {
+    assert!(is_test_attr(&["test"]));
+    assert!(is_test_attr(&["tokio", "test"]));
+    assert!(is_test_attr(&["self", "test"]));
+    assert!(!is_test_attr(&["rstest"]));
+    assert!(!is_test_attr(&["test_case"]));
+    assert!(!is_test_attr(&["tag"]));
+    assert!(!is_test_attr::<&str>(&[]));
+}
+
+#[test]
+fn test_is_any_test_attr() {
+    assert!(is_any_test_attr(&["test"]));
+    assert!(is_any_test_attr(&["test_log", "test"]));
+    assert!(is_any_test_attr(&["rstest"]));
+    assert!(is_any_test_attr(&["test_case", "test_case"]));
+    assert!(is_any_test_attr(&["proptest"]));
+    assert!(!is_any_test_attr(&["ignore"]));
+    assert!(!is_any_test_attr(&["case"]));
+}
@@ -0,0 +1,1193 @@
+use crate::config::*;
+use serial_test::serial;
+use std::{fs::File, io::Write, path::Path};
+use tempfile::{tempdir, TempDir};
+
+fn create_cargo_toml(content: &str) -> TempDir {
+    let tmp_dir = tempdir().unwrap();
+    write_cargo_toml(tmp_dir.path(), content);
+    tmp_dir
+}
+
+fn write_cargo_toml(dir_path: &Path, content: &str) {
+    std::fs::create_dir_all(dir_path).expect("failed to create dir");
+    let cargo_path = dir_path.join("Cargo.toml");
+    let mut cargo =
+        File::create(cargo_path).expect("failed to create Cargo.toml file");
+    cargo.write_all(content.as_bytes()).expect("cannot write");
+}
+
+fn in_manifest(dir_path: impl AsRef<Path>, error: ConfigError) -> ConfigError {
+    ConfigError::Manifest {
+        path: dir_path.as_ref().join("Cargo.toml"),
+        source: Box::new(error),
+    }
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_3_tags_ok() {
+    let content = r#"
+[Package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "tag2", "tag3"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_ok());
+
+    let config = config_result.unwrap();
+
+    assert_eq!(3, config.allowed_tags.len());
+    assert_eq!("tag1", config.allowed_tags[0]);
+    assert_eq!("tag2", config.allowed_tags[1]);
+    assert_eq!("tag3", config.allowed_tags[2]);
+    assert_eq!(vec![tmp_dir.path().join("Cargo.toml")], config.sources);
+}
+
+#[test]
+#[serial]
+fn test_create_config_from_manifest_dir_ok() {
+    let tmp_dir = create_cargo_toml(
+        r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1"]
+"#,
+    );
+    std::env::remove_var("CARGO_MANIFEST_DIR");
+
+    let config = ConfigFactory::from_manifest_dir(tmp_dir.path()).unwrap();
+    assert_eq!(vec!["tag1"], config.allowed_tags);
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_missing_file_ko() {
+    let tmp_dir = tempdir().unwrap();
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert!(matches!(
+        config_result.err().unwrap(),
+        ConfigError::Manifest { source, .. } if matches!(*source, ConfigError::ReadError(_))
+    ));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_toml_ko() {
+    let content = r#"
+[Pa
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert!(matches!(
+        config_result.err().unwrap(),
+        ConfigError::Manifest { source, .. } if matches!(*source, ConfigError::ParseError(_))
+    ));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_missing_allowed_tags_ko() {
+    let content = r#"
+[Package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::MissingTags),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_missing_metadata_ko() {
+    let content = r#"
+[Package]
+name = "consumer"
+version = "0.0.1"
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::MissingTags),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_duplicated_tag_ko() {
+    let content = r#"
+[Package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "tag1", "tag3"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::DuplicateTag("tag1".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_tag_format_ko() {
+    let content = r#"
+[Package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["?invalid", "tag2", "tag3"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::InvalidTagFormat("?invalid".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_reserved_tag_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "t"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::ReservedTag("t".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_operator_tag_ko() {
+    for operator in ["and", "or", "not"] {
+        let content = format!(
+            r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1"]
+
+[package.metadata.pinny.tags.tag1]
+aliases = ["{operator}"]
+"#
+        );
+
+        let tmp_dir = create_cargo_toml(&content);
+        std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+        let config_result = ConfigFactory::create();
+        assert_eq!(
+            in_manifest(&tmp_dir, ConfigError::ReservedTag(operator.into())),
+            config_result.err().unwrap()
+        );
+    }
+
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "not"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::ReservedTag("not".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_tags_array_ko() {
+    let content = r#"
+[Package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = "tag1"
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert!(config_result.is_err());
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::InvalidArrayFormat),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_tag_definitions_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "tag2"]
+
+[package.metadata.pinny.tags.tag2]
+description = "second tag"
+
+[package.metadata.pinny.tags.tag3]
+description = "third tag"
+
+[package.metadata.pinny.tags.tag4]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1", "tag2", "tag3", "tag4"], config.allowed_tags);
+    assert_eq!(None, config.description("tag1"));
+    assert_eq!(Some("second tag"), config.description("tag2"));
+    assert_eq!(Some("third tag"), config.description("tag3"));
+    assert_eq!(None, config.description("tag4"));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_only_tag_definitions_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny.tags]
+slow = { description = "slow tests" }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["slow"], config.allowed_tags);
+    assert_eq!(Some("slow tests"), config.description("slow"));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_tag_definition_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny.tags]
+slow = { description = "slow test", ignore = 1 }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::InvalidTagDefinition("slow".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_tags_table_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+tags = ["slow"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::InvalidTableFormat),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_aliases_and_implies_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["slow"]
+
+[package.metadata.pinny.tags]
+bitcoin = { aliases = ["btc"] }
+integration = { implies = ["slow", "bitcoin"] }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["slow", "bitcoin", "integration"], config.allowed_tags);
+    assert!(config.is_allowed("btc"));
+    assert_eq!("bitcoin", config.canonical("btc"));
+    assert_eq!("slow", config.canonical("slow"));
+    assert_eq!(vec!["slow", "bitcoin"], config.implied("integration"));
+    assert!(config.implied("slow").is_empty());
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_deprecated_tag_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["fast"]
+
+[package.metadata.pinny.tags]
+quick = { deprecated = "use fast" }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["fast", "quick"], config.allowed_tags);
+    assert_eq!(None, config.deprecation("fast"));
+    assert_eq!(Some("use fast"), config.deprecation("quick"));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_ignored_tags_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny.tags]
+fast = { ignore = false }
+slow = { ignore = true }
+requires_docker = { ignore = "needs a docker daemon" }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(None, config.ignore_reason("fast"));
+    assert_eq!(Some("pinny: tagged slow"), config.ignore_reason("slow"));
+    assert_eq!(
+        Some("pinny: tagged requires_docker, needs a docker daemon"),
+        config.ignore_reason("requires_docker")
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_groups_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["fast", "slow", "bitcoin", "stacks"]
+
+[package.metadata.pinny.groups]
+speed = { tags = ["fast", "slow"], rule = "exactly_one" }
+domain = { tags = ["bitcoin", "stacks"], rule = "required" }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(
+        vec![
+            TagGroup {
+                name: "domain".into(),
+                tags: vec!["bitcoin".into(), "stacks".into()],
+                rule: GroupRule::Required,
+            },
+            TagGroup {
+                name: "speed".into(),
+                tags: vec!["fast".into(), "slow".into()],
+                rule: GroupRule::ExactlyOne,
+            },
+        ],
+        config.groups
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_group_rule_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["fast", "slow"]
+
+[package.metadata.pinny.groups]
+speed = { tags = ["fast", "slow"], rule = "one" }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(
+            &tmp_dir,
+            ConfigError::InvalidGroupDefinition("speed".into())
+        ),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_unknown_group_tag_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["fast"]
+
+[package.metadata.pinny.groups]
+speed = { tags = ["fast", "slow"], rule = "exactly_one" }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::UnknownTag("slow".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_conflicts_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["unit", "network", "slow"]
+conflicts = [["unit", "network"], ["unit", "slow"]]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(
+        vec![
+            ("unit".to_string(), "network".to_string()),
+            ("unit".to_string(), "slow".to_string())
+        ],
+        config.conflicts
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_conflict_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["unit", "network", "slow"]
+conflicts = [["unit", "network", "slow"]]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(
+            &tmp_dir,
+            ConfigError::InvalidConflict(
+                r#"["unit", "network", "slow"]"#.into()
+            )
+        ),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_unknown_conflict_tag_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["unit"]
+conflicts = [["unit", "network"]]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::UnknownTag("network".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_duplicated_alias_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["slow"]
+
+[package.metadata.pinny.tags]
+bitcoin = { aliases = ["slow"] }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::DuplicateTag("slow".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_unknown_implied_tag_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny.tags]
+integration = { implies = ["slow"] }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::UnknownTag("slow".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_implies_cycle_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny.tags]
+tag1 = { implies = ["tag2"] }
+tag2 = { implies = ["tag3"] }
+tag3 = { implies = ["tag1"] }
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(
+            &tmp_dir,
+            ConfigError::ImpliesCycle("tag1 -> tag2 -> tag3 -> tag1".into())
+        ),
+        config_result.err().unwrap()
+    );
+}
+
+const WORKSPACE_CARGO_TOML: &str = r#"
+[workspace]
+members = ["member"]
+
+[workspace.metadata.pinny]
+allowed = ["tag1", "tag2"]
+
+[workspace.metadata.pinny.tags.tag2]
+description = "second tag"
+"#;
+
+#[test]
+#[serial]
+fn test_create_config_with_workspace_tags_ok() {
+    let tmp_dir = create_cargo_toml(WORKSPACE_CARGO_TOML);
+    let member_dir = tmp_dir.path().join("member");
+    write_cargo_toml(
+        &member_dir,
+        r#"
+[package]
+name = "member"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed.workspace = true
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &member_dir);
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1", "tag2"], config.allowed_tags);
+    assert_eq!(Some("second tag"), config.description("tag2"));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_workspace_tags_extended_ok() {
+    let tmp_dir = create_cargo_toml(WORKSPACE_CARGO_TOML);
+    let member_dir = tmp_dir.path().join("member");
+    write_cargo_toml(
+        &member_dir,
+        r#"
+[package]
+name = "member"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = { workspace = true, extend = ["tag3"] }
+
+[package.metadata.pinny.tags.tag4]
+description = "member tag"
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &member_dir);
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1", "tag2", "tag3", "tag4"], config.allowed_tags);
+    assert_eq!(Some("member tag"), config.description("tag4"));
+    assert_eq!(
+        vec![
+            member_dir.join("Cargo.toml"),
+            tmp_dir.path().join("Cargo.toml")
+        ],
+        config.sources
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_explicit_workspace_path_ok() {
+    let tmp_dir = create_cargo_toml(WORKSPACE_CARGO_TOML);
+    let member_dir = tmp_dir.path().join("nested").join("member");
+    write_cargo_toml(
+        &member_dir,
+        r#"
+[package]
+name = "member"
+version = "0.0.1"
+workspace = "../.."
+
+[package.metadata.pinny]
+allowed.workspace = true
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &member_dir);
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1", "tag2"], config.allowed_tags);
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_workspace_duplicated_tag_ko() {
+    let tmp_dir = create_cargo_toml(WORKSPACE_CARGO_TOML);
+    let member_dir = tmp_dir.path().join("member");
+    write_cargo_toml(
+        &member_dir,
+        r#"
+[package]
+name = "member"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = { workspace = true, extend = ["tag2"] }
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &member_dir);
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&member_dir, ConfigError::DuplicateTag("tag2".into())),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_workspace_missing_tags_ko() {
+    let tmp_dir = create_cargo_toml(
+        r#"
+[workspace]
+members = ["member"]
+"#,
+    );
+    let member_dir = tmp_dir.path().join("member");
+    write_cargo_toml(
+        &member_dir,
+        r#"
+[package]
+name = "member"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed.workspace = true
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &member_dir);
+
+    let config_result = ConfigFactory::create();
+    let error = config_result.err().unwrap();
+    assert_eq!(in_manifest(&tmp_dir, ConfigError::MissingTags), error);
+    assert!(error.to_string().ends_with(&format!(
+        "(in {})",
+        tmp_dir.path().join("Cargo.toml").display()
+    )));
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_missing_workspace_ko() {
+    let content = r#"
+[package]
+name = "member"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed.workspace = true
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::MissingWorkspace),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_workspace_inheritance_ko() {
+    let tmp_dir = create_cargo_toml(WORKSPACE_CARGO_TOML);
+    let member_dir = tmp_dir.path().join("member");
+    write_cargo_toml(
+        &member_dir,
+        r#"
+[package]
+name = "member"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed.workspace = false
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &member_dir);
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&member_dir, ConfigError::InvalidWorkspaceInheritance),
+        config_result.err().unwrap()
+    );
+}
+
+fn write_pinny_toml(file_path: &Path, content: &str) {
+    let mut file =
+        File::create(file_path).expect("failed to create pinny config file");
+    file.write_all(content.as_bytes()).expect("cannot write");
+}
+
+const PACKAGE_CARGO_TOML: &str = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+"#;
+
+#[test]
+#[serial]
+fn test_create_config_with_adjacent_pinny_toml_ok() {
+    let tmp_dir = create_cargo_toml(PACKAGE_CARGO_TOML);
+    let pinny_path = tmp_dir.path().join("pinny.toml");
+    write_pinny_toml(
+        &pinny_path,
+        r#"
+allowed = ["tag1", "tag2"]
+
+[tags.tag3]
+description = "third tag"
+"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1", "tag2", "tag3"], config.allowed_tags);
+    assert_eq!(Some("third tag"), config.description("tag3"));
+    assert_eq!(
+        vec![tmp_dir.path().join("Cargo.toml"), pinny_path],
+        config.sources
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_explicit_pinny_toml_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+config = "config/tags.toml"
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::fs::create_dir(tmp_dir.path().join("config")).unwrap();
+    write_pinny_toml(
+        &tmp_dir.path().join("config").join("tags.toml"),
+        r#"allowed = ["tag1"]"#,
+    );
+    // The explicit path takes precedence over the adjacent file
+    write_pinny_toml(
+        &tmp_dir.path().join("pinny.toml"),
+        r#"allowed = ["tag2"]"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1"], config.allowed_tags);
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_matching_metadata_and_pinny_toml_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "tag2"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    write_pinny_toml(
+        &tmp_dir.path().join("pinny.toml"),
+        r#"allowed = ["tag1", "tag2"]"#,
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert_eq!(vec!["tag1", "tag2"], config.allowed_tags);
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_conflicting_pinny_toml_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1", "tag2"]
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    let pinny_path = tmp_dir.path().join("pinny.toml");
+    write_pinny_toml(&pinny_path, r#"allowed = ["tag1"]"#);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::ConflictingConfig(pinny_path)),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_pinny_toml_ko() {
+    let tmp_dir = create_cargo_toml(PACKAGE_CARGO_TOML);
+    let pinny_path = tmp_dir.path().join("pinny.toml");
+    write_pinny_toml(&pinny_path, r#"allowed = ["Tag1"]"#);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        ConfigError::Manifest {
+            path: pinny_path,
+            source: Box::new(ConfigError::InvalidTagFormat("Tag1".into())),
+        },
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_config_path_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+config = true
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::InvalidConfigPath),
+        config_result.err().unwrap()
+    );
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_settings_ok() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1"]
+runtime_filter = true
+gate = "feature"
+registry = true
+require_tags = true
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config = ConfigFactory::create().unwrap();
+    assert!(config.runtime_filter);
+    assert_eq!(Some(GateMode::Feature), config.gate);
+    assert_eq!("pinny", config.gate_prefix());
+    assert!(config.registry);
+    assert!(config.require_tags);
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_runtime_crate() {
+    let cases = [
+        ("", None),
+        (
+            "[dev-dependencies]\npinny-runtime = \"0.0.2\"",
+            Some("pinny_runtime"),
+        ),
+        (
+            "[dependencies]\nruntime = { package = \"pinny-runtime\" }",
+            Some("runtime"),
+        ),
+        (
+            "[target.'cfg(unix)'.dev-dependencies]\npinny-runtime = \"0.0.2\"",
+            Some("pinny_runtime"),
+        ),
+        (
+            "[dev-dependencies]\npinny-runtime = { package = \"other\" }",
+            None,
+        ),
+    ];
+
+    for (dependencies, expected) in cases {
+        let content = format!(
+            r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1"]
+
+{dependencies}
+"#
+        );
+
+        let tmp_dir = create_cargo_toml(&content);
+        std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+        let config = ConfigFactory::create().unwrap();
+        assert_eq!(
+            expected.map(String::from),
+            config.runtime_crate,
+            "{dependencies}"
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_gate_prefix() {
+    let cases = [
+        ("\"mycrate\"", Ok("mycrate")),
+        ("\"_my_crate2\"", Ok("_my_crate2")),
+        ("\"my-crate\"", Err(())),
+        ("\"2crate\"", Err(())),
+        ("\"\"", Err(())),
+        ("true", Err(())),
+    ];
+
+    for (prefix, expected) in cases {
+        let content = format!(
+            r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1"]
+gate = "feature"
+gate_prefix = {prefix}
+"#
+        );
+
+        let tmp_dir = create_cargo_toml(&content);
+        std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+        let config_result = ConfigFactory::create();
+        match expected {
+            Ok(expected) => {
+                assert_eq!(expected, config_result.unwrap().gate_prefix());
+            }
+            Err(()) => assert_eq!(
+                in_manifest(
+                    &tmp_dir,
+                    ConfigError::InvalidSetting("gate_prefix".into())
+                ),
+                config_result.err().unwrap(),
+                "{prefix}"
+            ),
+        }
+    }
+}
+
+#[test]
+#[serial]
+fn test_create_config_with_invalid_gate_ko() {
+    let content = r#"
+[package]
+name = "consumer"
+version = "0.0.1"
+
+[package.metadata.pinny]
+allowed = ["tag1"]
+gate = "env"
+"#;
+
+    let tmp_dir = create_cargo_toml(content);
+    std::env::set_var("CARGO_MANIFEST_DIR", tmp_dir.path());
+
+    let config_result = ConfigFactory::create();
+    assert_eq!(
+        in_manifest(&tmp_dir, ConfigError::InvalidSetting("gate".into())),
+        config_result.err().unwrap()
+    );
+}
@@ -0,0 +1,269 @@
+use crate::config::Config;
+use crate::expression::*;
+use regex::Regex;
+
+fn tag(name: &str) -> Expr {
+    Expr::Tag(name.into())
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(Expr::Tag("slow".into()), Expr::parse("slow").unwrap());
+    assert_eq!(
+        Expr::And(
+            Box::new(tag("bitcoin")),
+            Box::new(Expr::Not(Box::new(Expr::Or(
+                Box::new(tag("slow")),
+                Box::new(tag("flaky"))
+            ))))
+        ),
+        Expr::parse("bitcoin and not (slow or flaky)").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_precedence() {
+    assert_eq!(
+        Expr::Or(
+            Box::new(tag("tag1")),
+            Box::new(Expr::And(
+                Box::new(Expr::Not(Box::new(tag("tag2")))),
+                Box::new(tag("tag3"))
+            ))
+        ),
+        Expr::parse("tag1 or not tag2 and tag3").unwrap()
+    );
+    assert_eq!(
+        Expr::And(
+            Box::new(Expr::And(Box::new(tag("tag1")), Box::new(tag("tag2")))),
+            Box::new(tag("tag3"))
+        ),
+        Expr::parse("tag1 and tag2 and tag3").unwrap()
+    );
+    assert_eq!(
+        Expr::parse("((tag1))and(not(tag2))").unwrap(),
+        Expr::parse("tag1 and not tag2").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_ko() {
+    use ExpressionError::*;
+    let error = |input: &str| Expr::parse(input).unwrap_err();
+
+    assert_eq!(Empty, error(""));
+    assert_eq!(Empty, error("   "));
+    assert_eq!(UnexpectedChar('&', 5), error("tag1 & tag2"));
+    assert_eq!(UnexpectedToken("tag2".into(), 5), error("tag1 tag2"));
+    assert_eq!(UnexpectedToken("and".into(), 0), error("and tag1"));
+    assert_eq!(UnexpectedToken(")".into(), 4), error("tag1)"));
+    assert_eq!(UnexpectedToken(")".into(), 1), error("()"));
+    assert_eq!(UnexpectedEnd("a tag"), error("tag1 or"));
+    assert_eq!(UnexpectedEnd("')'"), error("(tag1 or tag2"));
+    assert_eq!(InvalidTag("t".into()), error("t"));
+    assert_eq!(InvalidTag("Slow".into()), error("tag1 or Slow"));
+}
+
+#[test]
+fn test_matches() {
+    let expr = Expr::parse("bitcoin and not (slow or flaky)").unwrap();
+    assert!(expr.matches(&["bitcoin"]));
+    assert!(expr.matches(&["bitcoin", "network"]));
+    assert!(!expr.matches(&["bitcoin", "slow"]));
+    assert!(!expr.matches(&["flaky", "bitcoin"]));
+    assert!(!expr.matches::<&str>(&[]));
+}
+
+#[test]
+fn test_tags() {
+    let expr = Expr::parse("tag2 and not (tag1 or tag2)").unwrap();
+    assert_eq!(vec!["tag2", "tag1"], expr.tags());
+}
+
+#[test]
+fn test_display() {
+    for (input, expected) in [
+        ("tag1", "tag1"),
+        ("not not tag1", "not not tag1"),
+        ("tag1 and tag2 and tag3", "tag1 and tag2 and tag3"),
+        ("tag1 or tag2 and tag3", "tag1 or (tag2 and tag3)"),
+        ("(tag1 or tag2) and not tag3", "(tag1 or tag2) and not tag3"),
+        ("not (tag1 and tag2)", "not (tag1 and tag2)"),
+    ] {
+        let expr = Expr::parse(input).unwrap();
+        assert_eq!(expected, expr.to_string());
+        assert_eq!(expr, expected.parse().unwrap());
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let mut config = Config {
+        allowed_tags: vec!["tag1".into(), "tag2".into()],
+        ..Config::default()
+    };
+    config.aliases.insert("second".into(), "tag2".into());
+
+    let expr = Expr::parse("tag1 and not second").unwrap();
+    assert_eq!(
+        Expr::parse("tag1 and not tag2").unwrap(),
+        expr.resolve(&config).unwrap()
+    );
+
+    let expr = Expr::parse("tag1 or tag3").unwrap();
+    assert_eq!(
+        ExpressionError::NotAllowed(
+            "Invalid tag 'tag3', did you mean 'tag1' or 'tag2'?".into()
+        ),
+        expr.resolve(&config).unwrap_err()
+    );
+}
+
+#[test]
+fn test_to_nextest() {
+    let expr = Expr::parse("tag1 and not (tag2 or tag3)").unwrap();
+    let any = "(?:[a-su-z][a-z0-9_]*|t[a-z0-9_]+)";
+    let test = |tag: &str| {
+        format!("test(/(?:^|::)t::(?:{any}::)*{tag}::(?:{any}::)*t$/)")
+    };
+    assert_eq!(
+        format!(
+            "{} and not ({} or {})",
+            test("tag1"),
+            test("tag2"),
+            test("tag3")
+        ),
+        expr.to_nextest()
+    );
+}
+
+#[test]
+fn test_to_nextest_respects_delimiters() {
+    let expr = Expr::parse("tag1").unwrap();
+    let filterset = expr.to_nextest();
+    let regex = filterset
+        .strip_prefix("test(/")
+        .and_then(|rest| rest.strip_suffix("/)"))
+        .unwrap();
+    let regex = Regex::new(regex).unwrap();
+
+    for name in [
+        "test_hello::t::tag1::t",
+        "tests::test_hello::t::tag1::t",
+        "tests::test_hello::t::tag2::tag1::tag3::t",
+        "t::t::t::tag1::t",
+        "tag1::t::tag1::t",
+    ] {
+        assert!(regex.is_match(name), "{name} should match");
+    }
+    for name in [
+        "tag1::test_hello::t::tag2::t",
+        "tests::tag1::t::tag2::t",
+        "t::tag1::t::test_hello::t::tag2::t",
+        "tests::test_hello::t::tag10::t",
+        "tests::test_hello::t::xtag1::t",
+        "tests::tag1",
+        "tests::test_hello::t::tag1::t::extra",
+    ] {
+        assert!(!regex.is_match(name), "{name} should not match");
+    }
+}
+
+#[test]
+fn test_libtest_filters() {
+    let expr = Expr::parse("tag1 and not tag2").unwrap();
+    let names = [
+        "tests::test_1::t::tag1::t",
+        "tests::test_12::t::tag1::tag2::t",
+        "tag1::test_2::t::tag2::t",
+        "tests::tag1",
+        "t::tag1::t::test_3::t::tag3::t",
+    ];
+    assert_eq!(
+        vec!["tests::test_1::t::tag1::t"],
+        expr.libtest_filters(names)
+    );
+
+    let expr = Expr::parse("not tag1").unwrap();
+    assert_eq!(
+        vec!["tag1::test_2::t::tag2::t", "t::tag1::t::test_3::t::tag3::t"],
+        expr.libtest_filters(names)
+    );
+}
+
+#[test]
+fn test_to_nextest_untagged() {
+    let any = "(?:[a-su-z][a-z0-9_]*|t[a-z0-9_]+)";
+    let tagged = format!("test(/::t::(?:{any}::)+t$/)");
+    let test = |tag: &str| {
+        format!("test(/(?:^|::)t::(?:{any}::)*{tag}::(?:{any}::)*t$/)")
+    };
+
+    let expr = Expr::parse("tag1 or not tag2").unwrap();
+    assert_eq!(
+        format!("{} and ({} or not {})", tagged, test("tag1"), test("tag2")),
+        expr.to_nextest()
+    );
+
+    let expr = Expr::parse("tag1 and not tag2").unwrap();
+    assert_eq!(
+        format!("{} and not {}", test("tag1"), test("tag2")),
+        expr.to_nextest()
+    );
+}
+
+/// Select the test names matching a nextest filterset, made of `test(/<regex>/)`
+/// predicates, by evaluating it as an expression over the matching predicates
+fn nextest_selection<'a>(filterset: &str, names: &[&'a str]) -> Vec<&'a str> {
+    let predicate = Regex::new(r"test\(/(.*?)/\)").unwrap();
+    let mut regexes = Vec::new();
+    let expr =
+        predicate.replace_all(filterset, |captures: &regex::Captures| {
+            regexes.push(Regex::new(&captures[1]).unwrap());
+            format!("r{}", regexes.len() - 1)
+        });
+    let expr = Expr::parse(&expr).unwrap();
+    names
+        .iter()
+        .filter(|name| {
+            let matching: Vec<String> = regexes
+                .iter()
+                .enumerate()
+                .filter(|(_, regex)| regex.is_match(name))
+                .map(|(index, _)| format!("r{index}"))
+                .collect();
+            expr.matches(&matching)
+        })
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_same_selection_for_both_runners() {
+    let names = [
+        "tests::test_1::t::tag1::t",
+        "tests::test_12::t::tag1::tag2::t",
+        "tests::test_2::t::tag2::t",
+        "tests::test_3::t::tag3::t",
+        "tag1::test_4::t::tag3::t",
+        "tests::test_untagged",
+        "tests::tag1",
+        "t::tests::test_t",
+    ];
+    for expr in [
+        "tag1",
+        "not tag1",
+        "tag1 and not tag2",
+        "not (tag1 or tag2)",
+        "tag3 or not tag2",
+        "not tag1 or not tag2",
+        "not not tag1",
+    ] {
+        let expr = Expr::parse(expr).unwrap();
+        assert_eq!(
+            expr.libtest_filters(names),
+            nextest_selection(&expr.to_nextest(), &names),
+            "selection of {expr}"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::mangling::*;
+
+#[test]
+fn test_encode() {
+    assert_eq!(
+        "tests::test_hello::t::tag1::tag2::t",
+        encode("tests", "test_hello", &["tag1", "tag2"])
+    );
+    assert_eq!(
+        "test_hello::t::tag1::t",
+        encode("", "test_hello", &["tag1"])
+    );
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(
+        Some(TaggedTest {
+            module_path: "tests::nested".into(),
+            name: "test_hello".into(),
+            tags: vec!["tag1".into(), "tag2".into()],
+        }),
+        decode("tests::nested::test_hello::t::tag1::tag2::t")
+    );
+
+    let test = decode("test_hello::t::tag1::t").unwrap();
+    assert_eq!("", test.module_path);
+    assert_eq!("test_hello", test.name);
+    assert_eq!("test_hello::t::tag1::t", test.encode());
+}
+
+#[test]
+fn test_path() {
+    let test = decode("tests::nested::test_hello::t::tag1::t").unwrap();
+    assert_eq!("tests::nested::test_hello", test.path());
+
+    let test = decode("test_hello::t::tag1::t").unwrap();
+    assert_eq!("test_hello", test.path());
+}
+
+#[test]
+fn test_decode_untagged_ko() {
+    assert_eq!(None, decode("tests::test_hello"));
+    assert_eq!(None, decode("tests::test_hello::t::t"));
+    assert_eq!(None, decode("t::tag1::t"));
+    assert_eq!(None, decode(""));
+}
+
+#[test]
+fn test_decode_closure() {
+    let test =
+        decode("thirdparty::test_tokio::t::tag1::t::{{closure}}").unwrap();
+    assert_eq!("thirdparty", test.module_path);
+    assert_eq!("test_tokio", test.name);
+    assert_eq!(vec!["tag1"], test.tags);
+
+    let test = decode("test_tokio::t::tag1::t::{{closure}}::{{closure}}");
+    assert_eq!("test_tokio", test.unwrap().name);
+}
+
+#[test]
+fn test_decode_modules_named_as_delimiter() {
+    let test = decode("t::tests::t::t::t::tag1::tag2::t").unwrap();
+    assert_eq!("t::tests::t", test.module_path);
+    assert_eq!("t", test.name);
+    assert_eq!(vec!["tag1", "tag2"], test.tags);
+}
+
+#[test]
+fn test_decode_invalid_segments_ko() {
+    assert_eq!(None, decode("tests::test_hello::t::Tag1::t"));
+    assert_eq!(None, decode("tests::test_hello::t::tag1::{{closure}}::t"));
+    assert_eq!(None, decode("tests::<impl Foo>::test_hello::t::tag1::t"));
+    assert_eq!(None, decode("::test_hello::t::tag1::t"));
+}
+
+#[test]
+fn test_is_valid_tag() {
+    assert!(is_valid_tag("slow"));
+    assert!(is_valid_tag("requires_network2"));
+    assert!(!is_valid_tag("t"));
+    assert!(!is_valid_tag("Slow"));
+    assert!(!is_valid_tag("2slow"));
+    assert!(!is_valid_tag(""));
+}
+
+mod round_trip {
+    use crate::mangling::*;
+    use proptest::prelude::*;
+
+    /// Identifiers, with a bias towards the delimiter
+    fn identifier() -> impl Strategy<Value = String> {
+        prop_oneof![
+            1 => Just(DELIMITER.to_string()),
+            4 => "[a-zA-Z_][a-zA-Z0-9_]{0,8}".prop_filter("not `_`", |id| id != "_"),
+        ]
+    }
+
+    fn tag() -> impl Strategy<Value = String> {
+        "[a-z][a-z0-9_]{0,8}"
+            .prop_filter("not the delimiter", |tag| tag != DELIMITER)
+    }
+
+    fn tagged_test() -> impl Strategy<Value = TaggedTest> {
+        (
+            prop::collection::vec(identifier(), 0..4),
+            identifier(),
+            prop::collection::vec(tag(), 1..5),
+        )
+            .prop_map(|(modules, name, tags)| TaggedTest {
+                module_path: modules.join(SEPARATOR),
+                name,
+                tags,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_decode_encoded(test in tagged_test()) {
+            prop_assert_eq!(Some(test.clone()), decode(&test.encode()));
+        }
+
+        #[test]
+        fn test_decode_encoded_closure(test in tagged_test(), closures in 1..3usize) {
+            let path = format!("{}{}", test.encode(), "::{{closure}}".repeat(closures));
+            prop_assert_eq!(Some(test), decode(&path));
+        }
+
+        #[test]
+        fn test_encode_decoded(test in tagged_test()) {
+            let path = test.encode();
+            let decoded = decode(&path).unwrap();
+            prop_assert_eq!(path, decoded.encode());
+        }
+    }
+}
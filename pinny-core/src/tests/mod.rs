@@ -0,0 +1,5 @@
+mod attributes_test;
+mod config_test;
+mod expression_test;
+mod mangling_test;
+mod validation_test;
@@ -0,0 +1,172 @@
+use crate::config::{Config, GroupRule, TagDefinition, TagGroup};
+use crate::validation::*;
+
+fn config_with(allowed: &[&str]) -> Config {
+    Config {
+        allowed_tags: allowed.iter().map(ToString::to_string).collect(),
+        ..Config::default()
+    }
+}
+
+fn messages(violations: Vec<Violation>) -> Vec<String> {
+    violations
+        .into_iter()
+        .map(|violation| violation.message)
+        .collect()
+}
+
+#[test]
+fn test_check_labels() {
+    let mut config = config_with(&["tag1", "tag2"]);
+    config.aliases.insert("second".into(), "tag2".into());
+
+    assert!(check_labels(&["tag1", "second"], &config).is_empty());
+
+    assert_eq!(
+        vec![
+            Violation {
+                index: Some(2),
+                message: "Duplicated tag 'second'.".into()
+            },
+            Violation {
+                index: Some(3),
+                message: "Invalid tag 'tag3', did you mean 'tag1' or 'tag2'?"
+                    .into()
+            },
+        ],
+        check_labels(&["tag1", "tag2", "second", "tag3"], &config)
+    );
+}
+
+#[test]
+fn test_expand_labels() {
+    let mut config = config_with(&["tag1", "tag2", "tag3"]);
+    config.definitions.insert(
+        "tag3".into(),
+        TagDefinition {
+            implies: vec!["tag2".into()],
+            aliases: vec!["third".into()],
+            ..TagDefinition::default()
+        },
+    );
+    config.definitions.insert(
+        "tag2".into(),
+        TagDefinition {
+            implies: vec!["tag1".into()],
+            ..TagDefinition::default()
+        },
+    );
+    config.aliases.insert("third".into(), "tag3".into());
+
+    assert_eq!(
+        vec![("tag1".to_string(), 0)],
+        expand_labels(&["tag1"], &config)
+    );
+    assert_eq!(
+        vec![
+            ("tag2".to_string(), 0),
+            ("tag3".to_string(), 1),
+            ("tag1".to_string(), 0),
+        ],
+        expand_labels(&["tag2", "third"], &config)
+    );
+}
+
+#[test]
+fn test_check_groups() {
+    let mut config = config_with(&["fast", "slow", "bitcoin", "stacks"]);
+    config.groups = vec![
+        TagGroup {
+            name: "speed".into(),
+            tags: vec!["fast".into(), "slow".into()],
+            rule: GroupRule::ExactlyOne,
+        },
+        TagGroup {
+            name: "domain".into(),
+            tags: vec!["bitcoin".into(), "stacks".into()],
+            rule: GroupRule::Required,
+        },
+    ];
+
+    assert!(check_groups(&["fast", "bitcoin", "stacks"], &config).is_empty());
+
+    assert_eq!(
+        vec![Violation {
+            index: None,
+            message: "Missing tag from group 'speed': exactly one of [\"fast\", \"slow\"] is required.".into()
+        }],
+        check_groups(&["bitcoin"], &config)
+    );
+
+    let violations = check_groups(&["fast", "slow"], &config);
+    assert_eq!(Some(1), violations[0].index);
+    assert_eq!(
+        vec![
+            "Too many tags from group 'speed': 'fast' and 'slow' cannot be used together, only one of [\"fast\", \"slow\"] is allowed.",
+            "Missing tag from group 'domain': at least one of [\"bitcoin\", \"stacks\"] is required.",
+        ],
+        messages(violations)
+    );
+
+    config.groups[0].rule = GroupRule::AtMostOne;
+    assert!(check_groups(&["stacks"], &config).is_empty());
+}
+
+#[test]
+fn test_check_conflicts() {
+    let mut config = config_with(&["unit", "network", "slow"]);
+    config.conflicts = vec![("unit".into(), "network".into())];
+
+    assert!(check_conflicts(&["unit", "slow"], &config).is_empty());
+    assert_eq!(
+        vec![Violation {
+            index: Some(2),
+            message:
+                "Conflicting tags: 'unit' and 'network' cannot be used together."
+                    .into()
+        }],
+        check_conflicts(&["network", "slow", "unit"], &config)
+    );
+}
+
+#[test]
+fn test_check_rules() {
+    let mut config = config_with(&["unit", "network"]);
+    config.conflicts = vec![("unit".into(), "network".into())];
+    config.groups = vec![TagGroup {
+        name: "kind".into(),
+        tags: vec!["unit".into(), "network".into()],
+        rule: GroupRule::AtMostOne,
+    }];
+
+    assert_eq!(2, check_rules(&["unit", "network"], &config).len());
+    assert!(check_rules(&["unit"], &config).is_empty());
+}
+
+#[test]
+fn test_invalid_tag_message() {
+    let allowed: Vec<String> = (0..12).map(|i| format!("tag_{i}")).collect();
+    let config = Config {
+        allowed_tags: allowed,
+        ..Config::default()
+    };
+
+    assert_eq!(
+        "Invalid tag 'unknown'. Allowed tags are: [\"tag_0\", \"tag_1\", \"tag_2\", \"tag_3\", \"tag_4\", \"tag_5\", \"tag_6\", \"tag_7\", \"tag_8\", \"tag_9\"] and 2 more.",
+        invalid_tag_message("unknown", &config)
+    );
+    assert_eq!(
+        "Invalid tag 'tag_0x', did you mean 'tag_0'?",
+        invalid_tag_message("tag_0x", &config)
+    );
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(0, edit_distance("bitcoin", "bitcoin"));
+    assert_eq!(1, edit_distance("bitcon", "bitcoin"));
+    assert_eq!(1, edit_distance("bitcoin", "bitcoins"));
+    assert_eq!(2, edit_distance("slwo", "slow"));
+    assert_eq!(4, edit_distance("fast", "slow"));
+    assert_eq!(4, edit_distance("", "slow"));
+}
@@ -0,0 +1,250 @@
+//! Validation of the tags applied to a test, against the configuration.
+//!
+//! Checks work on plain tag names and report each [`Violation`] along with
+//! the position of the offending tag, so that callers can point to it
+//! (e.g. the `#[tag]` macro spans the compile error on the label).
+use crate::config::{Config, GroupRule};
+use std::collections::HashSet;
+
+/// Max number of allowed tags listed when suggesting alternatives to an invalid tag
+pub const MAX_LISTED_TAGS: usize = 10;
+
+/// Violation of a rule by the tags of a test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Position of the offending tag, `None` when the violation is about a missing tag
+    pub index: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    const fn at(index: usize, message: String) -> Self {
+        Self {
+            index: Some(index),
+            message,
+        }
+    }
+
+    const fn missing(message: String) -> Self {
+        Self {
+            index: None,
+            message,
+        }
+    }
+}
+
+/// Check the tag labels written for a test, that is:
+/// - uniques (no duplication, even through aliases)
+/// - allowed (by configuration)
+pub fn check_labels<S: AsRef<str>>(
+    labels: &[S],
+    config: &Config,
+) -> Vec<Violation> {
+    let mut uniques = HashSet::new();
+    let mut violations = Vec::new();
+
+    for (index, label) in labels.iter().enumerate() {
+        let label = label.as_ref();
+        if !uniques.insert(config.canonical(label)) {
+            violations.push(Violation::at(
+                index,
+                format!("Duplicated tag '{label}'."),
+            ));
+        } else if !config.is_allowed(label) {
+            violations
+                .push(Violation::at(index, invalid_tag_message(label, config)));
+        }
+    }
+    violations
+}
+
+/// Expand (already checked) tag labels, replacing aliases with their tag and
+/// adding the implied tags (transitively), without duplicates.
+///
+/// Each tag comes with the position of the label it originates from.
+pub fn expand_labels<S: AsRef<str>>(
+    labels: &[S],
+    config: &Config,
+) -> Vec<(String, usize)> {
+    fn push_unique(tags: &mut Vec<(String, usize)>, tag: &str, origin: usize) {
+        if !tags.iter().any(|(each, _)| each == tag) {
+            tags.push((tag.to_string(), origin));
+        }
+    }
+
+    let mut expanded = Vec::new();
+    for (index, label) in labels.iter().enumerate() {
+        push_unique(&mut expanded, config.canonical(label.as_ref()), index);
+    }
+
+    let mut index = 0;
+    while index < expanded.len() {
+        let (tag, origin) = expanded[index].clone();
+        for implied in config.implied(&tag) {
+            push_unique(&mut expanded, implied, origin);
+        }
+        index += 1;
+    }
+    expanded
+}
+
+/// Check if the (expanded) tags of a test satisfy the configured rules,
+/// that is tag groups and conflicts.
+pub fn check_rules<S: AsRef<str>>(
+    tags: &[S],
+    config: &Config,
+) -> Vec<Violation> {
+    let mut violations = check_groups(tags, config);
+    violations.extend(check_conflicts(tags, config));
+    violations
+}
+
+/// Check if the (expanded) tags of a test satisfy the rules of the tag groups:
+/// - `required`: at least one tag of the group
+/// - `exactly_one`: one and only one tag of the group
+/// - `at_most_one`: no more than one tag of the group
+///
+/// Exceeding tags are reported at their position.
+pub fn check_groups<S: AsRef<str>>(
+    tags: &[S],
+    config: &Config,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for group in &config.groups {
+        let name = &group.name;
+        let group_tags = &group.tags;
+        let found: Vec<(usize, &str)> = tags
+            .iter()
+            .map(AsRef::as_ref)
+            .enumerate()
+            .filter(|(_, tag)| group_tags.iter().any(|each| each == tag))
+            .collect();
+
+        match (group.rule, found.as_slice()) {
+            (GroupRule::Required, []) => violations.push(Violation::missing(
+                format!("Missing tag from group '{name}': at least one of {group_tags:?} is required."),
+            )),
+            (GroupRule::ExactlyOne, []) => violations.push(Violation::missing(
+                format!("Missing tag from group '{name}': exactly one of {group_tags:?} is required."),
+            )),
+            (GroupRule::ExactlyOne | GroupRule::AtMostOne, [(_, first), exceeding @ ..]) => {
+                for (index, tag) in exceeding {
+                    violations.push(Violation::at(
+                        *index,
+                        format!("Too many tags from group '{name}': '{first}' and '{tag}' cannot be used together, only one of {group_tags:?} is allowed."),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    violations
+}
+
+/// Check if the (expanded) tags of a test contain conflicting tags.
+///
+/// Each conflict is reported at the position of the latter of the two tags.
+pub fn check_conflicts<S: AsRef<str>>(
+    tags: &[S],
+    config: &Config,
+) -> Vec<Violation> {
+    let position =
+        |tag: &String| tags.iter().position(|each| each.as_ref() == tag);
+
+    config
+        .conflicts
+        .iter()
+        .filter_map(|(first, second)| {
+            let (first_pos, second_pos) = (position(first)?, position(second)?);
+            Some(Violation::at(
+                first_pos.max(second_pos),
+                format!("Conflicting tags: '{first}' and '{second}' cannot be used together."),
+            ))
+        })
+        .collect()
+}
+
+/// Build the error message for a tag not allowed by configuration.
+///
+/// Suggest the closest allowed tags (by edit distance) when any is close enough,
+/// otherwise list the allowed tags (truncated to [`MAX_LISTED_TAGS`]).
+/// In both cases, the description of the reported tags is appended.
+#[must_use]
+pub fn invalid_tag_message(tag: &str, config: &Config) -> String {
+    let suggestions = suggest_tags(tag, &config.allowed_tags);
+
+    let (mut message, listed) = if suggestions.is_empty() {
+        let listed: Vec<&String> =
+            config.allowed_tags.iter().take(MAX_LISTED_TAGS).collect();
+        let remaining = config.allowed_tags.len() - listed.len();
+        let message = if remaining > 0 {
+            format!(
+                "Invalid tag '{tag}'. Allowed tags are: {listed:?} and {remaining} more."
+            )
+        } else {
+            format!("Invalid tag '{tag}'. Allowed tags are: {listed:?}.")
+        };
+        (message, listed)
+    } else {
+        let alternatives = suggestions
+            .iter()
+            .map(|allowed| format!("'{allowed}'"))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        (
+            format!("Invalid tag '{tag}', did you mean {alternatives}?"),
+            suggestions,
+        )
+    };
+
+    for allowed in listed {
+        if let Some(description) = config.description(allowed) {
+            message = format!("{message}\n  - {allowed}: {description}");
+        }
+    }
+    message
+}
+
+/// Return the allowed tags closest to the given one, if close enough
+/// (that is within an edit distance of a third of the tag length).
+#[must_use]
+pub fn suggest_tags<'a>(
+    tag: &str,
+    allowed_tags: &'a [String],
+) -> Vec<&'a String> {
+    let threshold = (tag.len() / 3).max(1);
+    let distances: Vec<(usize, &String)> = allowed_tags
+        .iter()
+        .map(|allowed| (edit_distance(tag, allowed), allowed))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect();
+    let Some(min) = distances.iter().map(|(distance, _)| *distance).min()
+    else {
+        return Vec::new();
+    };
+    distances
+        .into_iter()
+        .filter(|(distance, _)| *distance == min)
+        .map(|(_, allowed)| allowed)
+        .collect()
+}
+
+/// Compute the edit (Levenshtein) distance between two strings
+#[must_use]
+pub fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution =
+                previous[j] + usize::from(left_char != *right_char);
+            current.push(
+                substitution.min(previous[j + 1] + 1).min(current[j] + 1),
+            );
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
@@ -0,0 +1,119 @@
+//! Runtime filtering of the tagged tests, by the `PINNY_INCLUDE` and
+//! `PINNY_EXCLUDE` tag expressions (see [`pinny_core::expression`]).
+//!
+//! With `runtime_filter = true` in the pinny config, `#[pinny::tag]` starts
+//! each tagged test with a call to [`skip`], returning early when filtered out.
+use pinny_core::expression::Expr;
+use std::process::ExitCode;
+use std::sync::OnceLock;
+
+/// Environment variable holding the expression of the tests to run
+pub const INCLUDE_VAR: &str = "PINNY_INCLUDE";
+/// Environment variable holding the expression of the tests not to run
+pub const EXCLUDE_VAR: &str = "PINNY_EXCLUDE";
+
+/// Tag expressions selecting the tests to run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub include: Option<Expr>,
+    pub exclude: Option<Expr>,
+}
+
+impl Filter {
+    /// Read the filter from the `PINNY_INCLUDE` and `PINNY_EXCLUDE` variables
+    ///
+    /// # Errors
+    ///
+    /// Return an error when a variable holds an invalid expression.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name| std::env::var(name).ok();
+        Self::parse(var(INCLUDE_VAR).as_deref(), var(EXCLUDE_VAR).as_deref())
+    }
+
+    /// Parse the filter expressions, the blank ones being no filter
+    ///
+    /// # Errors
+    ///
+    /// Return an error when an expression is invalid.
+    pub fn parse(
+        include: Option<&str>,
+        exclude: Option<&str>,
+    ) -> Result<Self, String> {
+        let parse = |var: &str, expr: Option<&str>| {
+            expr.filter(|expr| !expr.trim().is_empty())
+                .map(|expr| {
+                    Expr::parse(expr).map_err(|error| {
+                        format!("invalid tag expression in {var}: `{expr}` ({error})")
+                    })
+                })
+                .transpose()
+        };
+        Ok(Self {
+            include: parse(INCLUDE_VAR, include)?,
+            exclude: parse(EXCLUDE_VAR, exclude)?,
+        })
+    }
+
+    /// Check if a test with the given labels (tags and aliases) is to be run
+    #[must_use]
+    pub fn selects(&self, labels: &[&str]) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|expr| expr.matches(labels))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|expr| expr.matches(labels))
+    }
+}
+
+/// Check if a test is filtered out, reporting it when so.
+///
+/// The test is given by its tags and their aliases, as resolved from
+/// the pinny config by `#[pinny::tag]`, so that the expressions can refer
+/// to both. The filter is read once, from the environment.
+///
+/// # Panics
+///
+/// Panic when `PINNY_INCLUDE` or `PINNY_EXCLUDE` holds an invalid expression.
+#[must_use]
+pub fn skip(tags: &[&str], aliases: &[&str]) -> bool {
+    static FILTER: OnceLock<Filter> = OnceLock::new();
+    let filter = FILTER.get_or_init(|| {
+        Filter::from_env().unwrap_or_else(|error| panic!("pinny: {error}"))
+    });
+
+    let labels: Vec<&str> = tags.iter().chain(aliases).copied().collect();
+    if filter.selects(&labels) {
+        return false;
+    }
+    eprintln!(
+        "pinny: skipped, tags {tags:?} filtered out by {INCLUDE_VAR}/{EXCLUDE_VAR}"
+    );
+    true
+}
+
+/// Value returned by a test skipped at runtime, reported as passed
+#[diagnostic::on_unimplemented(
+    message = "pinny cannot skip a test returning `{Self}` at runtime",
+    note = "supported return types are `()`, `ExitCode` and `Result` of them"
+)]
+pub trait Skip {
+    fn skipped() -> Self;
+}
+
+impl Skip for () {
+    fn skipped() -> Self {}
+}
+
+impl Skip for ExitCode {
+    fn skipped() -> Self {
+        Self::SUCCESS
+    }
+}
+
+impl<T: Skip, E> Skip for Result<T, E> {
+    fn skipped() -> Self {
+        Ok(T::skipped())
+    }
+}
@@ -0,0 +1,169 @@
+//! # Pinny Runtime
+//!
+//! Runtime companion of [pinny](https://crates.io/crates/pinny), giving access to the registry
+//! of the tagged tests linked into the current binary (e.g. to build custom harnesses or reports).
+//!
+//! The registry is opt-in, enabled by `registry = true` in the pinny configuration:
+//!
+//! ```toml
+//! [package.metadata.pinny]
+//! allowed = ["slow", "bitcoin"]
+//! registry = true
+//! ```
+//!
+//! Each tagged test then registers an entry in a dedicated linker section,
+//! with no runtime dependency, and the entries can be iterated from the test binary:
+//!
+//! ```rust,no_run
+//! for entry in pinny_runtime::entries() {
+//!     println!("{} {:?}", entry.test_path(), entry.tags());
+//! }
+//! ```
+//!
+//! It also holds the [`filter`] of the tagged tests at runtime, enabled by
+//! `runtime_filter = true` and driven by the `PINNY_INCLUDE`/`PINNY_EXCLUDE` variables.
+//!
+//! ### Notes
+//!
+//! - The registry is supported on ELF (e.g. Linux) and Mach-O (e.g. macOS) targets,
+//!   on other targets (e.g. Windows, wasm) no entry is collected.
+//! - The layout of [`TestEntry`] must match the entries generated by `#[pinny::tag]`.
+
+pub mod filter;
+#[cfg(test)]
+mod tests;
+
+/// Registry entry of a tagged test.
+///
+/// The entries are generated by `#[pinny::tag]`, hence the fixed `repr(C)` layout.
+#[derive(Debug)]
+#[repr(C)]
+pub struct TestEntry {
+    name: &'static str,
+    path: &'static str,
+    tags: &'static [&'static str],
+}
+
+impl TestEntry {
+    /// Original name of the test function (e.g. `test_hello`)
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Tags of the test, inherited and implied ones included
+    #[must_use]
+    pub const fn tags(&self) -> &'static [&'static str] {
+        self.tags
+    }
+
+    /// Check if the test has a tag
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+
+    /// Module path of the test, crate name included (e.g. `my_crate::tests`)
+    #[must_use]
+    pub fn module_path(&self) -> &'static str {
+        // the registered path is the one of the module enclosing the test
+        // function: <module_path>::<test_name>::t::<tags...>
+        let tags_path = self
+            .tags
+            .iter()
+            .fold(String::new(), |path, tag| path + "::" + tag);
+        let suffix = format!("::{}::t{tags_path}", self.name);
+        self.path.strip_suffix(&suffix).unwrap_or(self.path)
+    }
+
+    /// Path of the test as reported by the test harness
+    /// (e.g. `tests::test_hello::t::slow::t`)
+    #[must_use]
+    pub fn test_path(&self) -> String {
+        // skip the crate name, not part of the test path
+        let path = self
+            .path
+            .split_once("::")
+            .map_or(self.path, |(_, path)| path);
+        format!("{path}::t")
+    }
+}
+
+/// Return the entries of all the tagged tests of the current binary.
+#[must_use]
+pub fn entries() -> &'static [TestEntry] {
+    section::entries()
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod section {
+    use super::TestEntry;
+
+    extern "Rust" {
+        #[link_name = "\x01section$start$__DATA$__pinny_tests"]
+        static START: TestEntry;
+        #[link_name = "\x01section$end$__DATA$__pinny_tests"]
+        static STOP: TestEntry;
+    }
+
+    // Ensure the section exists, even when no test is registered
+    #[used]
+    #[link_section = "__DATA,__pinny_tests,regular,no_dead_strip"]
+    static PLACEHOLDER: [TestEntry; 0] = [];
+
+    pub fn entries() -> &'static [TestEntry] {
+        // SAFETY: the section only contains `TestEntry` items
+        unsafe { super::between(&raw const START, &raw const STOP) }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    windows,
+    target_family = "wasm"
+)))]
+mod section {
+    use super::TestEntry;
+
+    extern "Rust" {
+        #[link_name = "__start_pinny_tests"]
+        static START: TestEntry;
+        #[link_name = "__stop_pinny_tests"]
+        static STOP: TestEntry;
+    }
+
+    // Ensure the section exists, even when no test is registered
+    #[used]
+    #[link_section = "pinny_tests"]
+    static PLACEHOLDER: [TestEntry; 0] = [];
+
+    pub fn entries() -> &'static [TestEntry] {
+        // SAFETY: the section only contains `TestEntry` items
+        unsafe { super::between(&raw const START, &raw const STOP) }
+    }
+}
+
+// No section delimiters (Windows) or no custom section of pointers (wasm)
+#[cfg(any(windows, target_family = "wasm"))]
+mod section {
+    use super::TestEntry;
+
+    pub const fn entries() -> &'static [TestEntry] {
+        &[]
+    }
+}
+
+/// Build the slice of the entries laid out between two symbols.
+///
+/// # Safety
+///
+/// `start` and `stop` must delimit a contiguous array of `TestEntry`.
+#[cfg(not(any(windows, target_family = "wasm")))]
+unsafe fn between(
+    start: *const TestEntry,
+    stop: *const TestEntry,
+) -> &'static [TestEntry] {
+    let len = (stop as usize - start as usize) / size_of::<TestEntry>();
+    std::slice::from_raw_parts(start, len)
+}
@@ -0,0 +1,43 @@
+use crate::filter::*;
+use std::process::ExitCode;
+
+#[test]
+fn test_filter_parse() {
+    let filter = Filter::parse(None, Some(" ")).unwrap();
+    assert_eq!(Filter::default(), filter);
+
+    let filter = Filter::parse(Some("slow and not third"), None).unwrap();
+    assert_eq!("slow and not third", filter.include.unwrap().to_string());
+    assert!(filter.exclude.is_none());
+}
+
+#[test]
+fn test_filter_parse_ko() {
+    assert_eq!(
+        "invalid tag expression in PINNY_INCLUDE: `slow and` (Unexpected end of expression, expected a tag)",
+        Filter::parse(Some("slow and"), None).unwrap_err()
+    );
+    assert_eq!(
+        "invalid tag expression in PINNY_EXCLUDE: `Slow` (Tag is not a valid tag name: Slow)",
+        Filter::parse(None, Some("Slow")).unwrap_err()
+    );
+}
+
+#[test]
+fn test_filter_selects() {
+    assert!(Filter::default().selects(&["slow"]));
+
+    let filter = Filter::parse(Some("slow or third"), Some("flaky")).unwrap();
+    assert!(filter.selects(&["slow"]));
+    assert!(filter.selects(&["tag3", "third"]));
+    assert!(!filter.selects(&["slow", "flaky"]));
+    assert!(!filter.selects(&["bitcoin"]));
+    assert!(!filter.selects(&[]));
+}
+
+#[test]
+fn test_skipped() {
+    let () = Skip::skipped();
+    assert_eq!(Ok(()), Result::<(), String>::skipped());
+    assert_eq!(ExitCode::SUCCESS, ExitCode::skipped());
+}
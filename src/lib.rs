@@ -18,6 +18,80 @@
 //! - The `allowed` field defines a whitelist of tags that can be applied to test functions.
 //! - Tags not listed here will result in a **compile-time error**.
 //!
+//! Tags can also be declared (or described) in the `tags` table, so that their description
+//! is reported when an invalid tag is used:
+//!
+//! ```toml
+//! [package.metadata.pinny.tags]
+//! allowed_tag2 = { description = "Tests requiring a running node" }
+//! ```
+//!
+//! A tag definition can also declare `aliases` (alternative names for the tag) and
+//! `implies` (tags applied along with it). Implied tags must not form cycles:
+//!
+//! ```toml
+//! [package.metadata.pinny.tags]
+//! allowed_tag1 = { aliases = ["tag1"], implies = ["allowed_tag2"] }
+//! ```
+//!
+//! A tag marked as `deprecated` is still allowed, but its usage raises a compile-time warning
+//! reporting the deprecation note:
+//!
+//! ```toml
+//! [package.metadata.pinny.tags]
+//! old_tag = { deprecated = "use allowed_tag1" }
+//! ```
+//!
+//! Tests tagged with a tag declaring `ignore` (`true` or a reason) are ignored by default,
+//! unless they already carry an `#[ignore]` attribute:
+//!
+//! ```toml
+//! [package.metadata.pinny.tags]
+//! slow_tag = { ignore = "takes minutes" }
+//! ```
+//!
+//! Tags can be organized in `groups`, each one with a rule (`required`, `exactly_one` or
+//! `at_most_one`) the tags of every test must satisfy:
+//!
+//! ```toml
+//! [package.metadata.pinny.groups]
+//! speed = { tags = ["allowed_tag1", "allowed_tag2"], rule = "exactly_one" }
+//! ```
+//!
+//! Pairs of tags that cannot be used together can be declared as `conflicts`:
+//!
+//! ```toml
+//! [package.metadata.pinny]
+//! conflicts = [["allowed_tag1", "allowed_tag2"]]
+//! ```
+//!
+//! ### Workspace
+//!
+//! Tags can be shared across a workspace by declaring them in the workspace root `Cargo.toml`
+//! and inheriting them in each member, optionally extended with member specific tags:
+//!
+//! ```toml
+//! # workspace Cargo.toml
+//! [workspace.metadata.pinny]
+//! allowed = ["allowed_tag1", "allowed_tag2"]
+//!
+//! # member Cargo.toml
+//! [package.metadata.pinny]
+//! allowed = { workspace = true, extend = ["member_tag"] }
+//! ```
+//!
+//! ### Config file
+//!
+//! The settings can also be provided by a standalone `pinny.toml` file (same schema,
+//! without the `package.metadata.pinny` prefix), located next to the `Cargo.toml`
+//! or at the path given by `package.metadata.pinny.config`, which takes precedence.
+//! When both the file and the `Cargo.toml` metadata declare tags, they must agree.
+//!
+//! ```toml
+//! # pinny.toml
+//! allowed = ["allowed_tag1", "allowed_tag2"]
+//! ```
+//!
 //! ## Usage
 //!
 //! After configuration, you can annotate your test functions with tags like this:
@@ -39,6 +113,38 @@
 //! }
 //! ```
 //!
+//! The attribute can also be applied to an inline module to tag all the tests within it,
+//! merging the module tags with the ones declared by each test:
+//!
+//! ```rust,ignore
+//! use pinny::tag;
+//!
+//! #[tag(allowed_tag1)]
+//! mod tests {
+//!     use pinny::tag;
+//!
+//!     #[test]
+//!     fn test_tagged_by_module() {}
+//!
+//!     #[tag(allowed_tag2)]
+//!     #[test]
+//!     fn test_tagged_by_both() {}
+//! }
+//! ```
+//!
+//! The tags of a test can be retrieved from its body with [`current_tags!()`](crate::current_tags!),
+//! for example to pick a fixture depending on them:
+//!
+//! ```rust,ignore
+//! use pinny::{current_tags, tag};
+//!
+//! #[tag(allowed_tag1)]
+//! #[test]
+//! fn test_hello() {
+//!     let large = current_tags!().contains(&"allowed_tag1");
+//! }
+//! ```
+//!
 //! ### Notes
 //!
 //! - The `#[tag(...)]` macro must appear **before** the `#[test]` attribute.
@@ -63,6 +169,35 @@
 //!
 //! This command will only run tests tagged with `allowed_tag1`.
 //!
+//! ### Runtime filtering
+//!
+//! With `runtime_filter = true` in the pinny config, tagged tests are also filtered
+//! at runtime by the `PINNY_INCLUDE` and `PINNY_EXCLUDE` tag expressions
+//! (tags combined with `and`, `or`, `not` and parentheses), evaluated by the `pinny-runtime`
+//! crate (a dev-dependency then, else the tagged tests fail to compile).
+//! Filtered out tests return early, but the ones expected to panic (`#[should_panic]`).
+//! As libtest cannot skip a running test, they are reported as passed, not as skipped:
+//!
+//! ```bash
+//! PINNY_EXCLUDE="allowed_tag1 or allowed_tag2" cargo test
+//! ```
+//!
+//! ### Compile-time gating
+//!
+//! With `gate = "cfg"` (or `gate = "feature"`) in the pinny config, tagged tests are compiled
+//! conditionally: once `pinny_only` is set, only the tests with a tag selected by `pinny_tag_<tag>` are built.
+//! The `pinny` prefix can be changed with `gate_prefix`, and the cfg have to be declared
+//! to the check-cfg of the crate (`[lints.rust] unexpected_cfgs`):
+//!
+//! ```bash
+//! RUSTFLAGS="--cfg pinny_only --cfg pinny_tag_allowed_tag1" cargo test
+//! ```
+//!
+//! ### Registry
+//!
+//! With `registry = true` in the pinny config, every tagged test is registered in a linker section
+//! of the test binary (name, module path and tags), to be iterated with the `pinny-runtime` crate.
+//!
 //! ### Notes
 //!
 //! - tags are traslated into module path and are enclosed between `t` delimiters
@@ -81,6 +216,8 @@ use proc_macro::TokenStream;
 ///
 /// The attribute should be placed before `#[test]` attribute.
 ///
+/// When placed on an inline module, all the tests within it get tagged.
+///
 /// # Example
 ///
 /// Specify the attribute on a per-test basis:
@@ -97,3 +234,25 @@ use proc_macro::TokenStream;
 pub fn tag(attrs: TokenStream, item: TokenStream) -> TokenStream {
     tag::macro_impl(attrs, item)
 }
+
+/// A procedural macro returning the tags of the current test.
+///
+/// The tags (as `&[&str]`) are the ones applied to the test,
+/// including the inherited and implied ones, aliases being resolved.
+/// It can only be used from the body of a tagged test.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use pinny::{current_tags, tag};
+///
+/// #[tag(allowed_tag1)]
+/// #[test]
+/// fn test_hello() {
+///   assert!(current_tags!().contains(&"allowed_tag1"));
+/// }
+/// ```
+#[proc_macro]
+pub fn current_tags(input: TokenStream) -> TokenStream {
+    tag::current_tags_impl(input)
+}
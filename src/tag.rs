@@ -1,16 +1,18 @@
-use crate::config::Config;
+use crate::config::{self, Config, GateMode};
+use pinny_core::{attributes, mangling, validation};
 
 use proc_macro::{Span, TokenStream};
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use std::collections::HashSet;
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    parse::{Parse, Parser},
+    parenthesized,
+    parse::{Parse, ParseStream, Parser},
+    parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
-    token::Comma,
-    Attribute, Error, Ident, ItemFn, Meta, MetaNameValue, PathArguments,
-    PathSegment, Result,
+    token::{self, Comma},
+    Attribute, Error, Ident, Item, ItemFn, ItemMod, Meta, MetaNameValue,
+    PathArguments, PathSegment, Result,
 };
 
 /// Representation of a comma separated tag label list
@@ -20,13 +22,18 @@ pub type TagLabels = Punctuated<Ident, Comma>;
 ///
 /// This also help to convert from `proc_macro::TokenStream` to `proc_macro2::TokenStream`
 pub fn macro_impl(attrs: TokenStream, item: TokenStream) -> TokenStream {
-    let config_result = Config::get_instance();
+    let config_result = config::instance();
 
     match config_result {
         Ok(config) => {
-            resolve_tag(attrs.into(), item.into(), &config.allowed_tags)
-                .unwrap_or_else(Error::into_compile_error)
-                .into()
+            let sources = track_sources(config);
+            let result = resolve_tag(attrs.into(), item.into(), config)
+                .unwrap_or_else(Error::into_compile_error);
+            quote! {
+              #sources
+              #result
+            }
+            .into()
         }
         Err(error) => Error::new(Span::call_site().into(), error.to_string())
             .into_compile_error()
@@ -34,6 +41,18 @@ pub fn macro_impl(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// implementation for procedural macro `pinny::current_tags!()`
+///
+/// Refer to the `PINNY_TAGS` constant generated next to the tagged test function.
+pub fn current_tags_impl(input: TokenStream) -> TokenStream {
+    if let Some(token) = TokenStream2::from(input).into_iter().next() {
+        return Error::new(token.span(), "current_tags!() takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    quote! { self::PINNY_TAGS }.into()
+}
+
 /// A tag macro used like this:
 ///
 /// #[tag(tag1, tag2)]
@@ -45,17 +64,125 @@ pub fn macro_impl(attrs: TokenStream, item: TokenStream) -> TokenStream {
 /// produce the following params:
 /// - `args`: `tag1, tag2`
 /// - `item`: `#[test] fn test_hello() { assert!(true); }`
+///
+/// The macro can also be applied to an inline module, tagging every test within it.
 pub fn resolve_tag(
     args: TokenStream2,
     item: TokenStream2,
-    allowed_labels: &Vec<String>,
+    config: &Config,
 ) -> Result<TokenStream2> {
-    let mut tags = parse_tag_labels(args)?;
-    let fn_item = ItemFn::parse.parse2(item)?;
+    let TagArgs { inherited, tags } = TagArgs::parse.parse2(args)?;
+    let item = Item::parse.parse2(item)?;
+
+    // meant for the enclosing modules only, but writable by anyone:
+    // the inherited labels go through the same validation and expansion
+    validate_tag_labels(&inherited, config)?;
+    let mut labels = expand_tag_labels(&inherited, config);
+    let result = match item {
+        Item::Fn(fn_item) => {
+            validate_no_tag_attr(&fn_item)?;
+            validate_tag_labels(&tags, config)?;
+            extend_unique(&mut labels, expand_tag_labels(&tags, config));
+            validate_tag_rules(&labels, config, &tags)?;
+            tag_fn(fn_item, labels, config)
+        }
+        Item::Mod(mod_item) => {
+            validate_tag_labels(&tags, config)?;
+            extend_unique(&mut labels, expand_tag_labels(&tags, config));
+            tag_mod(mod_item, &labels, config)?
+        }
+        other => return Err(Error::new_spanned(
+            other,
+            "#[pinny::tag] can only be applied to functions or inline modules.",
+        )),
+    };
+
+    let warnings = deprecation_warnings(&tags, config);
+    Ok(quote! {
+      #warnings
+      #result
+    })
+}
+
+/// Arguments of the tag attribute: `[inherited(<tags...>),] <tags...>`
+///
+/// The `inherited` labels are the ones applied by an enclosing tagged module,
+/// and are meant for internal use only (yet validated as any other label).
+pub struct TagArgs {
+    pub inherited: TagLabels,
+    pub tags: TagLabels,
+}
 
-    validate_no_tag_attr(&fn_item)?;
-    validate_tag_labels(&tags, allowed_labels)?;
+impl Parse for TagArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut inherited = TagLabels::new();
+        if input.peek(Ident) && input.peek2(token::Paren) {
+            let keyword: Ident = input.parse()?;
+            if keyword != "inherited" {
+                return Err(Error::new_spanned(keyword, "expected a tag"));
+            }
+            let content;
+            parenthesized!(content in input);
+            inherited = TagLabels::parse_terminated(&content)?;
+            if !input.is_empty() {
+                input.parse::<Comma>()?;
+            }
+        }
+        let tags = parse_tag_labels(input.parse()?)?;
+        Ok(Self { inherited, tags })
+    }
+}
+
+/// Register the files the configuration was read from as dependencies
+/// of the expansion, so that editing them triggers a recompilation.
+///
+/// `proc_macro::tracked_path` is not stable yet, hence the hidden `include_bytes!`.
+/// Only the existing files can be tracked this way: creating a config file
+/// (e.g. a `pinny.toml` next to the manifest) doesn't trigger a recompilation,
+/// until a tracked file (the `Cargo.toml`, always a source) changes.
+pub fn track_sources(config: &Config) -> TokenStream2 {
+    config
+        .sources
+        .iter()
+        .filter_map(|source| source.to_str())
+        .map(|source| {
+            quote! {
+              const _: &[u8] = include_bytes!(#source);
+            }
+        })
+        .collect()
+}
+
+/// Produce a compile-time warning for each deprecated tag label.
+///
+/// Warnings are raised by referencing a `#[deprecated]` item,
+/// spanned on the label so that the warning points to the use site.
+pub fn deprecation_warnings(tags: &TagLabels, config: &Config) -> TokenStream2 {
+    tags.iter()
+        .filter_map(|tag| {
+            let tag_str = tag.to_string();
+            let canonical = config.canonical(&tag_str);
+            let note = config.deprecation(canonical)?;
+            let message =
+                format!("pinny tag '{canonical}' is deprecated: {note}");
+            Some(quote_spanned! {tag.span()=>
+              const _: () = {
+                #[deprecated(note = #message)]
+                #[allow(non_camel_case_types)]
+                struct #tag;
+                let _ = #tag;
+              };
+            })
+        })
+        .collect()
+}
 
+/// Tag a function, rewriting it as `<fn_name>::t::<tags...>::t`
+fn tag_fn(
+    fn_item: ItemFn,
+    mut tags: TagLabels,
+    config: &Config,
+) -> TokenStream2 {
     let ItemFn {
         attrs: mut fn_attrs,
         vis,
@@ -63,12 +190,48 @@ pub fn resolve_tag(
         block: fn_block,
     } = fn_item;
 
+    // Ignore the test by default when a tag asks so, unless already ignored
+    if fn_attrs.iter().any(is_test_attr)
+        && !fn_attrs.iter().any(|attr| attr.path().is_ident("ignore"))
+    {
+        if let Some(reason) = tags
+            .iter()
+            .find_map(|tag| config.ignore_reason(&tag.to_string()))
+        {
+            fn_attrs.push(parse_quote! { #[ignore = #reason] });
+        }
+    }
+
+    // Tests expected to panic would fail when returning early
+    let guard = if config.runtime_filter
+        && fn_attrs.iter().any(is_test_attr)
+        && !fn_attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("should_panic"))
+    {
+        runtime_guard(&tags, config)
+    } else {
+        TokenStream2::new()
+    };
+
+    let tag_names = tags.iter().map(ToString::to_string);
+    let tags_const = quote! {
+      #[doc(hidden)]
+      #[allow(dead_code)]
+      pub const PINNY_TAGS: &[&str] = &[#(#tag_names),*];
+    };
+
+    let gate = config
+        .gate
+        .filter(|_| fn_attrs.iter().any(is_test_attr))
+        .map(|mode| gate_predicate(mode, config.gate_prefix(), &tags));
+
     // Implement tags list delimeter for anti-clash or strict test filtering
     // the new test path become like this: <test_path>::original_test_name::t::tags_path::t
 
     // start tags delimiter: inserting an initial artificial module
-    let tags_delimiter = "t";
-    let first_tag = Ident::new(tags_delimiter, Span::call_site().into());
+    let tags_delimiter = mangling::DELIMITER;
+    let first_tag = Ident::new(tags_delimiter, proc_macro2::Span::call_site());
     tags.insert(0, first_tag.clone());
 
     // end tags delimiter: renaming the test function
@@ -79,9 +242,19 @@ pub fn resolve_tag(
     disambiguate_any_test_attr(&mut fn_attrs);
 
     //
+    let entry = if config.registry && fn_attrs.iter().any(is_test_attr) {
+        registry_entry(&original_test_name)
+    } else {
+        TokenStream2::new()
+    };
+
+    // The tags are exposed next to the test, for `pinny::current_tags!()`
     let mut result = quote! {
+      #tags_const
+      #entry
       #(#fn_attrs)*
       pub #fn_sign {
+        #guard
         #fn_block
       }
     };
@@ -108,17 +281,228 @@ pub fn resolve_tag(
     // and custom test attribute in `disambiguate_any_test_attr(..)`.
     // Not importing `std::prelude::v1::test` directly,
     // because that would conflict with potential user imports.
-    result = quote! {
+    let Some(gate) = gate else {
+        return quote! {
+          use std::prelude::v1::*;
+          #[allow(unused_imports)]
+          #vis use #original_test_name::#tags_path::#new_test_name as #original_test_name;
+          #[doc(hidden)]
+          pub mod #original_test_name {
+            use super::*;
+            #result
+          }
+        };
+    };
+
+    // When gated, the test (and so its re-export) may not be compiled at all:
+    // the re-export goes through a glob import, which is fine with being empty.
+    // The cfg are left to the check-cfg of the crate (`unexpected_cfgs` lint),
+    // catching the typos in the selected tags once declared.
+    quote! {
       use std::prelude::v1::*;
       #[allow(unused_imports)]
-      #vis use #original_test_name::#tags_path::#new_test_name as #original_test_name;
+      #vis use #original_test_name::__pinny_gate::*;
       #[doc(hidden)]
       pub mod #original_test_name {
         use super::*;
+        pub mod __pinny_gate {
+          #[cfg(#gate)]
+          pub use super::#tags_path::#new_test_name as #original_test_name;
+        }
+        #[cfg(#gate)]
         #result
       }
+    }
+}
+
+/// Produce the registry entry of a test, collected in a linker section.
+///
+/// The entry layout matches `pinny_runtime::TestEntry`, and is meant to be
+/// emitted next to the test function, within the innermost tag module.
+pub fn registry_entry(test_name: &Ident) -> TokenStream2 {
+    let name = test_name.to_string();
+    quote! {
+      const _: () = {
+        #[repr(C)]
+        struct TestEntry {
+          name: &'static str,
+          path: &'static str,
+          tags: &'static [&'static str],
+        }
+
+        #[used]
+        #[cfg_attr(
+          any(target_os = "macos", target_os = "ios"),
+          link_section = "__DATA,__pinny_tests,regular,no_dead_strip"
+        )]
+        #[cfg_attr(
+          not(any(
+            target_os = "macos",
+            target_os = "ios",
+            windows,
+            target_family = "wasm"
+          )),
+          link_section = "pinny_tests"
+        )]
+        static ENTRY: TestEntry = TestEntry {
+          name: #name,
+          path: ::core::module_path!(),
+          tags: PINNY_TAGS,
+        };
+      };
+    }
+}
+
+/// Produce the `cfg` predicate compiling a test with the given tags.
+///
+/// Tests are always compiled, unless `<prefix>_only` is set. In that case
+/// only the tests having a tag selected with `<prefix>_tag_<tag>` are compiled.
+pub fn gate_predicate(
+    mode: GateMode,
+    prefix: &str,
+    tags: &TagLabels,
+) -> TokenStream2 {
+    let only = format!("{prefix}_only");
+    let names = tags.iter().map(|tag| format!("{prefix}_tag_{tag}"));
+    match mode {
+        GateMode::Cfg => {
+            let only = Ident::new(&only, proc_macro2::Span::call_site());
+            let names = names
+                .map(|name| Ident::new(&name, proc_macro2::Span::call_site()));
+            quote! { any(not(#only), #(#names),*) }
+        }
+        GateMode::Feature => {
+            quote! { any(not(feature = #only), #(feature = #names),*) }
+        }
+    }
+}
+
+/// Produce a guard skipping the test at runtime, when its tags don't match
+/// the `PINNY_INCLUDE` or match the `PINNY_EXCLUDE` tag expressions.
+///
+/// The filtering is left to `pinny_runtime::filter`, given the tags and their
+/// aliases (the expressions can refer to both, not knowing the config).
+/// As libtest has no notion of skipped test, the test returns early and passes.
+///
+/// `pinny-runtime` must be a dependency of the package, otherwise the guard
+/// is a compile error naming it (rather than an unresolved path).
+pub fn runtime_guard(tags: &TagLabels, config: &Config) -> TokenStream2 {
+    let Some(runtime_crate) = &config.runtime_crate else {
+        return quote! {
+          ::core::compile_error!(
+            "`runtime_filter = true` requires the `pinny-runtime` crate: add it to the [dev-dependencies] of the package."
+          );
+        };
+    };
+    let runtime_crate =
+        Ident::new(runtime_crate, proc_macro2::Span::call_site());
+
+    let mut aliases: Vec<&String> = config
+        .aliases
+        .iter()
+        .filter(|(_, tag)| tags.iter().any(|each| each == tag))
+        .map(|(alias, _)| alias)
+        .collect();
+    aliases.sort();
+    let tags = tags.iter().map(ToString::to_string);
+
+    quote! {
+      if ::#runtime_crate::filter::skip(&[#(#tags),*], &[#(#aliases),*]) {
+        return ::#runtime_crate::filter::Skip::skipped();
+      }
+    }
+}
+
+/// Tag every test within an inline module.
+///
+/// Tags are merged (without duplicates) with the ones already declared
+/// by each function or nested module through a `#[tag]` attribute.
+fn tag_mod(
+    mut mod_item: ItemMod,
+    tags: &TagLabels,
+    config: &Config,
+) -> Result<TokenStream2> {
+    let Some((_, items)) = mod_item.content.as_mut() else {
+        return Err(Error::new_spanned(
+            &mod_item,
+            "#[pinny::tag] can only be applied to inline modules: `mod name { ... }`.",
+        ));
     };
-    Ok(result)
+
+    if mod_item.attrs.iter().any(is_tag_attr) {
+        return Err(Error::new_spanned(
+            &mod_item.ident,
+            "Only one #[pinny::tag] per module is allowed.",
+        ));
+    }
+
+    let mut errors: Option<Error> = None;
+    tag_mod_items(items, tags, config, &mut errors);
+    errors.map_or_else(|| Ok(quote! { #mod_item }), Err)
+}
+
+/// Tag test functions and nested modules items, collecting any error.
+///
+/// Items already having a `#[tag]` attribute get the module tags as `inherited`,
+/// leaving the expansion (and the validation of their own tags) to the attribute itself.
+fn tag_mod_items(
+    items: &mut [Item],
+    tags: &TagLabels,
+    config: &Config,
+    errors: &mut Option<Error>,
+) {
+    for item in items.iter_mut() {
+        match item {
+            Item::Fn(fn_item) if fn_item.attrs.iter().any(is_tag_attr) => {
+                inherit_tags(&mut fn_item.attrs, tags);
+            }
+            Item::Fn(fn_item)
+                if fn_item.attrs.iter().any(is_third_party_test_attr) =>
+            {
+                let name = &fn_item.sig.ident;
+                let message = format!(
+                    "Test `{name}` cannot be tagged by its module: tests of \
+                     third-party attributes (e.g. `#[rstest]`) require \
+                     `#[pinny::tag(...)]` on the function itself."
+                );
+                combine_error(errors, Error::new_spanned(name, message));
+            }
+            Item::Fn(fn_item) if fn_item.attrs.iter().any(is_test_attr) => {
+                match validate_tag_rules(tags, config, &fn_item.sig.ident) {
+                    Ok(()) => {
+                        *item = Item::Verbatim(tag_fn(
+                            fn_item.clone(),
+                            tags.clone(),
+                            config,
+                        ));
+                    }
+                    Err(error) => combine_error(errors, error),
+                }
+            }
+            Item::Mod(ItemMod { attrs, .. })
+                if attrs.iter().any(is_tag_attr) =>
+            {
+                inherit_tags(attrs, tags);
+            }
+            Item::Mod(ItemMod {
+                content: Some((_, nested_items)),
+                ..
+            }) => tag_mod_items(nested_items, tags, config, errors),
+            _ => {}
+        }
+    }
+}
+
+/// Rewrite the `#[tag]` attribute of an item, so that it inherits the given tags
+fn inherit_tags(attrs: &mut [Attribute], tags: &TagLabels) {
+    if let Some(attr) = attrs.iter_mut().find(|attr| is_tag_attr(attr)) {
+        let path = attr.path().clone();
+        let args = match &attr.meta {
+            Meta::List(list) => list.tokens.clone(),
+            Meta::Path(_) | Meta::NameValue(_) => TokenStream2::new(),
+        };
+        attr.meta = parse_quote! { #path(inherited(#tags), #args) };
+    }
 }
 
 /// No further tag on the function must exists.
@@ -139,35 +523,78 @@ pub fn validate_no_tag_attr(func: &ItemFn) -> Result<()> {
 /// - uniques (no duplication)
 /// - allowed (by configuration)
 ///
-/// Return error in case the a tag is not valid.
-pub fn validate_tag_labels(
-    tags: &TagLabels,
-    allowed_labels: &Vec<String>,
+/// Return error in case any tag is not valid, reporting all the invalid tags
+/// at once, each one spanned on the offending label.
+pub fn validate_tag_labels(tags: &TagLabels, config: &Config) -> Result<()> {
+    let violations = validation::check_labels(&label_names(tags), config);
+    into_result(violations, tags, tags)
+}
+
+/// Check if the (expanded) labels of a test satisfy the configured rules,
+/// that is tag groups and conflicts.
+///
+/// Return error in case any rule is violated, reporting all the violations at once.
+pub fn validate_tag_rules(
+    labels: &TagLabels,
+    config: &Config,
+    missing_at: impl ToTokens,
 ) -> Result<()> {
-    let mut uniques = HashSet::new();
+    let violations = validation::check_rules(&label_names(labels), config);
+    into_result(violations, labels, missing_at)
+}
 
-    for each_tag in tags {
-        let each_tag_str = each_tag.to_string();
+/// Convert rule violations into a combined error, each one spanned on the
+/// offending label, or on `missing_at` when about a missing tag.
+fn into_result(
+    violations: Vec<validation::Violation>,
+    labels: &TagLabels,
+    missing_at: impl ToTokens,
+) -> Result<()> {
+    let mut errors: Option<Error> = None;
+    for violation in violations {
+        let error = match violation.index {
+            Some(index) => {
+                Error::new_spanned(&labels[index], violation.message)
+            }
+            None => Error::new_spanned(&missing_at, violation.message),
+        };
+        combine_error(&mut errors, error);
+    }
+    errors.map_or(Ok(()), Err)
+}
 
-        // Check for duplicated tag
-        if !uniques.insert(each_tag_str.clone()) {
-            return Err(Error::new_spanned(
-                tags,
-                format!("Duplicated tag '{each_tag_str}'."),
-            ));
-        }
+/// Return the names of the tag labels
+fn label_names(tags: &TagLabels) -> Vec<String> {
+    tags.iter().map(ToString::to_string).collect()
+}
 
-        // Check for not allowed tag
-        if !allowed_labels.contains(&each_tag_str) {
-            return Err(Error::new_spanned(
-                tags,
-                format!(
-                    "Invalid tag '{each_tag_str}'. Allowed tags are: {allowed_labels:?}."),
-            ));
+/// Append tag labels, skipping the ones already present
+fn extend_unique(tags: &mut TagLabels, others: TagLabels) {
+    for tag in others {
+        if !tags.iter().any(|each| *each == tag) {
+            tags.push(tag);
         }
     }
+}
 
-    Ok(())
+/// Expand tag labels, replacing aliases with their tag and
+/// adding the implied tags (transitively), without duplicates.
+///
+/// Labels are expected to be already validated.
+/// Implied tags are spanned on the label implying them.
+pub fn expand_tag_labels(tags: &TagLabels, config: &Config) -> TagLabels {
+    validation::expand_labels(&label_names(tags), config)
+        .into_iter()
+        .map(|(tag, origin)| Ident::new(&tag, tags[origin].span()))
+        .collect()
+}
+
+/// Combine an error with the ones collected so far
+fn combine_error(errors: &mut Option<Error>, error: Error) {
+    match errors.as_mut() {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error),
+    }
 }
 
 /// Parse a list of tag labels e.g. (`tag1, tag2`).
@@ -185,20 +612,35 @@ pub fn parse_tag_labels(args: TokenStream2) -> Result<TagLabels> {
     }
 }
 
-/// Check if an attribute is a tag macro type
+/// Check if an attribute is a tag macro type,
+/// i.e. `#[tag]`, `#[tag(..)]` or `#[pinny::tag(..)]`
 pub fn is_tag_attr(attr: &Attribute) -> bool {
-    let path = match &attr.meta {
-        Meta::Path(path) => path, //path-only attribute #[tag]
-        Meta::List(list) => &list.path, //with args attrites #[tag(..)] or #[pinny::tag(..)]
-        Meta::NameValue(_) => return false,
-    };
+    !matches!(attr.meta, Meta::NameValue(_))
+        && attributes::is_tag_attr(&path_segments(attr.path()))
+}
 
-    let segments: Vec<_> = path.segments.iter().map(|s| &s.ident).collect();
-    match segments.as_slice() {
-        [ident] if *ident == "tag" => true,
-        [first, second] if *first == "pinny" && *second == "tag" => true,
-        _ => false,
-    }
+/// Check if an attribute is a test macro type.
+///
+/// Beside the built-in `#[test]`, any attribute path ending with `test` is considered,
+/// so to support third-party test attributes (e.g. `#[tokio::test]`)
+pub fn is_test_attr(attr: &Attribute) -> bool {
+    attributes::is_test_attr(&path_segments(attr.path()))
+}
+
+/// Check if an attribute is a third-party test one, that the macro
+/// cannot rewrite (e.g. `#[rstest]`, `#[test_case(..)]`)
+pub fn is_third_party_test_attr(attr: &Attribute) -> bool {
+    let segments = path_segments(attr.path());
+    attributes::is_any_test_attr(&segments)
+        && !attributes::is_test_attr(&segments)
+}
+
+/// Return the segments of a path, e.g. `["tokio", "test"]`
+fn path_segments(path: &syn::Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
 }
 
 /// Rewrite any `#[test]` attribute to use `#[self::test]` syntax.
@@ -1,2 +1 @@
-mod config_test;
 mod tag_test;
@@ -1,8 +1,16 @@
+use crate::config::{Config, GateMode, GroupRule, TagDefinition, TagGroup};
 use crate::tag::*;
 use proc_macro2::TokenStream;
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 use syn::{parse_quote, Attribute, ItemFn};
 
+fn config_with(allowed: &[&str]) -> Config {
+    Config {
+        allowed_tags: allowed.iter().map(ToString::to_string).collect(),
+        ..Config::default()
+    }
+}
+
 #[test]
 fn test_parse_tag_labels() {
     let input: TokenStream = "tag1, tag2".parse().unwrap();
@@ -20,6 +28,27 @@ fn test_parse_tag_labels() {
     assert_eq!("tag2", labels[1]);
 }
 
+#[test]
+fn test_parse_tag_args() {
+    fn to_strings(tags: &TagLabels) -> Vec<String> {
+        tags.iter().map(ToString::to_string).collect()
+    }
+
+    let args: TagArgs = parse_quote!(tag1, tag2);
+    assert!(args.inherited.is_empty());
+    assert_eq!(vec!["tag1", "tag2"], to_strings(&args.tags));
+
+    let args: TagArgs = parse_quote!(inherited(tag1, tag3), tag2);
+    assert_eq!(vec!["tag1", "tag3"], to_strings(&args.inherited));
+    assert_eq!(vec!["tag2"], to_strings(&args.tags));
+
+    let result = syn::parse2::<TagArgs>(quote!(inherited(tag1)));
+    assert!(result.is_err());
+
+    let result = syn::parse2::<TagArgs>(quote!(other(tag1), tag2));
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_validate_no_tag_attr() {
     let func: ItemFn = parse_quote! {
@@ -39,7 +68,7 @@ fn test_validate_no_tag_attr() {
 
 #[test]
 fn test_validate_tag_labels() {
-    let allowed = vec!["tag1".into(), "tag2".into()];
+    let allowed = config_with(&["tag1", "tag2"]);
 
     //valid
     let tags: TagLabels = parse_quote!(tag1, tag2);
@@ -47,11 +76,20 @@ fn test_validate_tag_labels() {
     assert!(result.is_ok());
 
     //invalid
+    let tags: TagLabels = parse_quote!(tag1, other);
+    let result = validate_tag_labels(&tags, &allowed);
+    assert!(result.is_err());
+    assert_eq!(
+        "Invalid tag 'other'. Allowed tags are: [\"tag1\", \"tag2\"].",
+        result.unwrap_err().to_string()
+    );
+
+    //invalid, with suggestion
     let tags: TagLabels = parse_quote!(tag1, tag3);
     let result = validate_tag_labels(&tags, &allowed);
     assert!(result.is_err());
     assert_eq!(
-        "Invalid tag 'tag3'. Allowed tags are: [\"tag1\", \"tag2\"].",
+        "Invalid tag 'tag3', did you mean 'tag1' or 'tag2'?",
         result.unwrap_err().to_string()
     );
 
@@ -62,6 +100,223 @@ fn test_validate_tag_labels() {
     assert_eq!("Duplicated tag 'tag1'.", result.unwrap_err().to_string());
 }
 
+#[test]
+fn test_validate_tag_labels_reports_all_errors() {
+    let allowed = config_with(&["tag1", "tag2"]);
+
+    let tags: TagLabels = parse_quote!(tag1, tag2, tag1, other);
+    let result = validate_tag_labels(&tags, &allowed);
+    let messages: Vec<String> = result
+        .unwrap_err()
+        .into_iter()
+        .map(|error| error.to_string())
+        .collect();
+    assert_eq!(
+        vec![
+            "Duplicated tag 'tag1'.".to_string(),
+            "Invalid tag 'other'. Allowed tags are: [\"tag1\", \"tag2\"]."
+                .to_string(),
+        ],
+        messages
+    );
+}
+
+fn config_with_definitions() -> Config {
+    let mut config = config_with(&["tag1", "tag2", "tag3"]);
+    config.definitions.insert(
+        "tag3".into(),
+        TagDefinition {
+            implies: vec!["tag2".into()],
+            aliases: vec!["third".into()],
+            ..TagDefinition::default()
+        },
+    );
+    config.definitions.insert(
+        "tag2".into(),
+        TagDefinition {
+            implies: vec!["tag1".into()],
+            ..TagDefinition::default()
+        },
+    );
+    config.aliases.insert("third".into(), "tag3".into());
+    config
+}
+
+#[test]
+fn test_validate_tag_labels_with_aliases() {
+    let config = config_with_definitions();
+
+    let tags: TagLabels = parse_quote!(third, tag1);
+    assert!(validate_tag_labels(&tags, &config).is_ok());
+
+    let tags: TagLabels = parse_quote!(tag3, third);
+    let result = validate_tag_labels(&tags, &config);
+    assert_eq!("Duplicated tag 'third'.", result.unwrap_err().to_string());
+}
+
+#[test]
+fn test_deprecation_warnings() {
+    let mut config = config_with(&["tag1", "tag2"]);
+    config.definitions.insert(
+        "tag2".into(),
+        TagDefinition {
+            deprecated: Some("use tag1".into()),
+            aliases: vec!["second".into()],
+            ..TagDefinition::default()
+        },
+    );
+    config.aliases.insert("second".into(), "tag2".into());
+
+    let tags: TagLabels = parse_quote!(tag1);
+    assert!(deprecation_warnings(&tags, &config).is_empty());
+
+    let tags: TagLabels = parse_quote!(tag1, second);
+    let warnings = deprecation_warnings(&tags, &config).to_string();
+    assert!(warnings.contains("pinny tag 'tag2' is deprecated: use tag1"));
+    assert!(warnings.contains("struct second"));
+}
+
+#[test]
+fn test_track_sources() {
+    let mut config = config_with(&["tag1"]);
+    assert!(track_sources(&config).is_empty());
+
+    config.sources.push("/path/to/Cargo.toml".into());
+    assert_eq!(
+        quote! { const _: &[u8] = include_bytes!("/path/to/Cargo.toml"); }
+            .to_string(),
+        track_sources(&config).to_string()
+    );
+}
+
+#[test]
+fn test_validate_tag_groups() {
+    fn messages(tags: &TagLabels, config: &Config) -> Vec<String> {
+        validate_tag_rules(tags, config, tags).map_or_else(
+            |error| error.into_iter().map(|e| e.to_string()).collect(),
+            |()| Vec::new(),
+        )
+    }
+
+    let mut config = config_with(&["fast", "slow", "bitcoin", "stacks"]);
+    config.groups = vec![
+        TagGroup {
+            name: "speed".into(),
+            tags: vec!["fast".into(), "slow".into()],
+            rule: GroupRule::ExactlyOne,
+        },
+        TagGroup {
+            name: "domain".into(),
+            tags: vec!["bitcoin".into(), "stacks".into()],
+            rule: GroupRule::Required,
+        },
+    ];
+
+    let tags: TagLabels = parse_quote!(fast, bitcoin, stacks);
+    assert!(messages(&tags, &config).is_empty());
+
+    let tags: TagLabels = parse_quote!(bitcoin);
+    assert_eq!(
+        vec!["Missing tag from group 'speed': exactly one of [\"fast\", \"slow\"] is required."],
+        messages(&tags, &config)
+    );
+
+    let tags: TagLabels = parse_quote!(fast, slow);
+    assert_eq!(
+        vec![
+            "Too many tags from group 'speed': 'fast' and 'slow' cannot be used together, only one of [\"fast\", \"slow\"] is allowed.",
+            "Missing tag from group 'domain': at least one of [\"bitcoin\", \"stacks\"] is required.",
+        ],
+        messages(&tags, &config)
+    );
+
+    config.groups[0].rule = GroupRule::AtMostOne;
+    let tags: TagLabels = parse_quote!(stacks);
+    assert!(messages(&tags, &config).is_empty());
+}
+
+#[test]
+fn test_validate_tag_conflicts() {
+    let mut config = config_with(&["unit", "network", "slow"]);
+    config.conflicts = vec![("unit".into(), "network".into())];
+
+    let tags: TagLabels = parse_quote!(unit, slow);
+    assert!(validate_tag_rules(&tags, &config, &tags).is_ok());
+
+    let tags: TagLabels = parse_quote!(network, slow, unit);
+    let result = validate_tag_rules(&tags, &config, &tags);
+    assert_eq!(
+        "Conflicting tags: 'unit' and 'network' cannot be used together.",
+        result.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_expand_tag_labels() {
+    fn to_strings(tags: &TagLabels) -> Vec<String> {
+        tags.iter().map(ToString::to_string).collect()
+    }
+
+    let config = config_with_definitions();
+
+    let tags: TagLabels = parse_quote!(tag1);
+    assert_eq!(vec!["tag1"], to_strings(&expand_tag_labels(&tags, &config)));
+
+    let tags: TagLabels = parse_quote!(tag3);
+    assert_eq!(
+        vec!["tag3", "tag2", "tag1"],
+        to_strings(&expand_tag_labels(&tags, &config))
+    );
+
+    let tags: TagLabels = parse_quote!(tag1, third);
+    assert_eq!(
+        vec!["tag1", "tag3", "tag2"],
+        to_strings(&expand_tag_labels(&tags, &config))
+    );
+}
+
+#[test]
+fn test_validate_tag_labels_with_descriptions() {
+    let mut config = config_with(&["tag1", "tag2"]);
+    config.definitions.insert(
+        "tag2".into(),
+        TagDefinition {
+            description: Some("second tag".into()),
+            ..TagDefinition::default()
+        },
+    );
+
+    let tags: TagLabels = parse_quote!(other);
+    let result = validate_tag_labels(&tags, &config);
+    assert_eq!(
+        "Invalid tag 'other'. Allowed tags are: [\"tag1\", \"tag2\"].\n  - tag2: second tag",
+        result.unwrap_err().to_string()
+    );
+
+    let tags: TagLabels = parse_quote!(tag22);
+    let result = validate_tag_labels(&tags, &config);
+    assert_eq!(
+        "Invalid tag 'tag22', did you mean 'tag2'?\n  - tag2: second tag",
+        result.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_validate_tag_labels_with_many_tags() {
+    let allowed: Vec<String> = (0..12).map(|i| format!("tag_{i}")).collect();
+    let config = Config {
+        allowed_tags: allowed,
+        ..Config::default()
+    };
+
+    let tags: TagLabels = parse_quote!(unknown);
+    let result = validate_tag_labels(&tags, &config);
+    assert_eq!(
+        "Invalid tag 'unknown'. Allowed tags are: [\"tag_0\", \"tag_1\", \"tag_2\", \"tag_3\", \"tag_4\", \"tag_5\", \"tag_6\", \"tag_7\", \"tag_8\", \"tag_9\"] and 2 more.",
+        result.unwrap_err().to_string()
+    );
+}
+
 #[test]
 fn test_recognize_tag_attribute() {
     let attrs: Vec<Attribute> = vec![
@@ -81,6 +336,278 @@ fn test_recognize_tag_attribute() {
     assert!(!is_tag_attr(&attrs[5]));
 }
 
+#[test]
+fn test_recognize_test_attribute() {
+    let attrs: Vec<Attribute> = vec![
+        parse_quote!(#[test]),
+        parse_quote!(#[tokio::test]),
+        parse_quote!(#[tokio::test(flavor = "multi_thread")]),
+        parse_quote!(#[self::test]),
+        parse_quote!(#[tag(tag1)]),
+        parse_quote!(#[ignore]),
+        parse_quote!(#[test_case]),
+    ];
+
+    assert!(is_test_attr(&attrs[0]));
+    assert!(is_test_attr(&attrs[1]));
+    assert!(is_test_attr(&attrs[2]));
+    assert!(is_test_attr(&attrs[3]));
+    assert!(!is_test_attr(&attrs[4]));
+    assert!(!is_test_attr(&attrs[5]));
+    assert!(!is_test_attr(&attrs[6]));
+
+    assert!(!is_third_party_test_attr(&attrs[0]));
+    assert!(!is_third_party_test_attr(&attrs[1]));
+    assert!(!is_third_party_test_attr(&attrs[4]));
+    assert!(is_third_party_test_attr(&attrs[6]));
+    assert!(is_third_party_test_attr(&parse_quote!(#[rstest])));
+}
+
+#[test]
+fn test_resolve_tag_on_module() {
+    fn to_string(tokens: &TokenStream) -> String {
+        tokens.to_string().split_whitespace().collect()
+    }
+
+    let config = config_with(&["tag1", "tag2"]);
+    let item = quote! {
+        mod tests {
+            #[tag(tag2, tag1)]
+            #[test]
+            fn test_tagged() {}
+
+            #[test]
+            fn test_untagged() {}
+
+            fn helper() {}
+        }
+    };
+
+    let result = resolve_tag(quote!(tag1), item, &config).unwrap();
+    let result = to_string(&result);
+
+    assert!(result
+        .contains("#[tag(inherited(tag1),tag2,tag1)]#[test]fntest_tagged(){}"));
+    assert!(result.contains("pubmodtest_untagged{"));
+    assert!(result.contains("pubmodtag1{"));
+    assert!(result.contains("fnhelper(){}"));
+}
+
+#[test]
+fn test_resolve_tag_with_inherited() {
+    let config = config_with_definitions();
+    let item = quote! {
+        #[test]
+        fn test_inherited() {}
+    };
+
+    let result =
+        resolve_tag(quote!(inherited(third), tag1), item.clone(), &config)
+            .unwrap();
+    let result: String = result.to_string().split_whitespace().collect();
+    assert!(result.contains("test_inherited::t::tag3::tag2::tag1::t"));
+
+    let result =
+        resolve_tag(quote!(inherited(not_allowed), tag1), item, &config);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_resolve_tag_with_runtime_filter() {
+    let mut config = config_with(&["tag1", "tag2"]);
+    let item = quote! {
+        #[test]
+        fn test_filtered() -> std::io::Result<()> { Ok(()) }
+    };
+
+    let result = resolve_tag(quote!(tag1), item.clone(), &config).unwrap();
+    assert!(!result.to_string().contains("pinny_runtime"));
+
+    config.runtime_filter = true;
+    config.runtime_crate = Some("pinny_runtime".into());
+    let result = resolve_tag(quote!(tag1, tag2), item, &config).unwrap();
+    let result: String = result.to_string().split_whitespace().collect();
+    assert!(result.contains(
+        r#"if::pinny_runtime::filter::skip(&["tag1","tag2"],&[]){return::pinny_runtime::filter::Skip::skipped();}"#
+    ));
+
+    let item = quote! {
+        #[test]
+        #[should_panic]
+        fn test_panicking() { panic!() }
+    };
+    let result = resolve_tag(quote!(tag1), item, &config).unwrap();
+    assert!(!result.to_string().contains("pinny_runtime"));
+
+    let item = quote! {
+        fn not_a_test() -> bool { true }
+    };
+    let result = resolve_tag(quote!(tag1), item, &config).unwrap();
+    assert!(!result.to_string().contains("pinny_runtime"));
+}
+
+#[test]
+fn test_runtime_guard() {
+    let mut config = config_with_definitions();
+    let guard = runtime_guard(&parse_quote!(tag3, tag2), &config).to_string();
+    assert!(guard.contains("compile_error"));
+    assert!(guard.contains("`pinny-runtime`"));
+
+    config.runtime_crate = Some("runtime".into());
+    let guard = runtime_guard(&parse_quote!(tag3, tag2), &config).to_string();
+    let guard: String = guard.split_whitespace().collect();
+    assert!(guard.starts_with(
+        r#"if::runtime::filter::skip(&["tag3","tag2"],&["third"])"#
+    ));
+}
+
+#[test]
+fn test_resolve_tag_with_ignored_tag() {
+    let mut config = config_with(&["tag1", "slow"]);
+    config.definitions.insert(
+        "slow".into(),
+        TagDefinition {
+            ignore: Some("pinny: tagged slow".into()),
+            ..TagDefinition::default()
+        },
+    );
+
+    let item = quote! {
+        #[test]
+        fn test_slow() {}
+    };
+    let result = resolve_tag(quote!(tag1), item.clone(), &config).unwrap();
+    assert!(!result.to_string().contains("ignore"));
+
+    let result = resolve_tag(quote!(tag1, slow), item, &config).unwrap();
+    assert!(result
+        .to_string()
+        .contains(r#"# [ignore = "pinny: tagged slow"]"#));
+
+    let item = quote! {
+        #[test]
+        #[ignore]
+        fn test_slow() {}
+    };
+    let result = resolve_tag(quote!(slow), item, &config).unwrap();
+    assert_eq!(1, result.to_string().matches("ignore").count());
+
+    let item = quote! {
+        fn not_a_test() {}
+    };
+    let result = resolve_tag(quote!(slow), item, &config).unwrap();
+    assert!(!result.to_string().contains("ignore"));
+}
+
+#[test]
+fn test_resolve_tag_with_tags_const() {
+    let config = config_with_definitions();
+    let item = quote! {
+        #[test]
+        fn test_tags() {}
+    };
+
+    let result = resolve_tag(quote!(third), item, &config).unwrap();
+    let result: String = result.to_string().split_whitespace().collect();
+    assert!(result.contains(
+        r#"pubmodtag1{usesuper::*;#[doc(hidden)]#[allow(dead_code)]pubconstPINNY_TAGS:&[&str]=&["tag3","tag2","tag1"];"#
+    ));
+}
+
+#[test]
+fn test_resolve_tag_with_registry() {
+    let mut config = config_with(&["tag1"]);
+    let item = quote! {
+        #[test]
+        fn test_registered() {}
+    };
+
+    let result = resolve_tag(quote!(tag1), item.clone(), &config).unwrap();
+    assert!(!result.to_string().contains("pinny_tests"));
+
+    config.registry = true;
+    let result = resolve_tag(quote!(tag1), item, &config).unwrap();
+    let result: String = result.to_string().split_whitespace().collect();
+    assert!(result.contains(
+        r#"staticENTRY:TestEntry=TestEntry{name:"test_registered",path:::core::module_path!(),tags:PINNY_TAGS,};"#
+    ));
+
+    let item = quote! {
+        fn not_a_test() {}
+    };
+    let result = resolve_tag(quote!(tag1), item, &config).unwrap();
+    assert!(!result.to_string().contains("pinny_tests"));
+}
+
+#[test]
+fn test_gate_predicate() {
+    let tags: TagLabels = parse_quote!(tag1, tag2);
+    assert_eq!(
+        quote! { any(not(pinny_only), pinny_tag_tag1, pinny_tag_tag2) }
+            .to_string(),
+        gate_predicate(GateMode::Cfg, "pinny", &tags).to_string()
+    );
+    assert_eq!(
+        quote! {
+            any(
+                not(feature = "pinny_only"),
+                feature = "pinny_tag_tag1",
+                feature = "pinny_tag_tag2"
+            )
+        }
+        .to_string(),
+        gate_predicate(GateMode::Feature, "pinny", &tags).to_string()
+    );
+    assert_eq!(
+        quote! {
+            any(
+                not(feature = "mycrate_only"),
+                feature = "mycrate_tag_tag1",
+                feature = "mycrate_tag_tag2"
+            )
+        }
+        .to_string(),
+        gate_predicate(GateMode::Feature, "mycrate", &tags).to_string()
+    );
+}
+
+#[test]
+fn test_resolve_tag_with_gate() {
+    fn to_string(tokens: &TokenStream) -> String {
+        tokens.to_string().split_whitespace().collect()
+    }
+
+    let mut config = config_with(&["tag1"]);
+    config.gate = Some(GateMode::Cfg);
+
+    let item = quote! {
+        #[test]
+        fn test_gated() {}
+    };
+    let result = to_string(&resolve_tag(quote!(tag1), item, &config).unwrap());
+    assert!(result.contains("usetest_gated::__pinny_gate::*;"));
+    assert!(
+        result.contains("#[cfg(any(not(pinny_only),pinny_tag_tag1))]pubmodt{")
+    );
+
+    config.gate = Some(GateMode::Feature);
+    config.gate_prefix = Some("mycrate".into());
+    let item = quote! {
+        #[test]
+        fn test_gated() {}
+    };
+    let result = to_string(&resolve_tag(quote!(tag1), item, &config).unwrap());
+    assert!(result.contains(
+        r#"#[cfg(any(not(feature="mycrate_only"),feature="mycrate_tag_tag1"))]"#
+    ));
+
+    let item = quote! {
+        fn not_a_test() {}
+    };
+    let result = to_string(&resolve_tag(quote!(tag1), item, &config).unwrap());
+    assert!(!result.contains("cfg"));
+}
+
 #[test]
 fn test_disambiguate_any_test_attr() {
     fn to_string(attr: &Attribute) -> String {
@@ -0,0 +1,7 @@
+use pinny::tag;
+
+#[tag(tagg1)]
+#[test]
+fn test_compilation_failure() {}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use pinny::tag;
+
+#[tag(tag1, tagg2, tag1, zzz)]
+#[test]
+fn test_compilation_failure() {}
+
+fn main() {}
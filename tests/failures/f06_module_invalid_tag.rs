@@ -0,0 +1,16 @@
+use pinny::tag;
+
+#[tag(tag1)]
+mod tests {
+    use pinny::tag;
+
+    #[tag(tag2, tagg3)]
+    #[test]
+    fn test_compilation_failure() {}
+
+    #[tag]
+    #[test]
+    fn test_empty_tag_failure() {}
+}
+
+fn main() {}
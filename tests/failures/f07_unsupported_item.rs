@@ -0,0 +1,9 @@
+use pinny::tag;
+
+#[tag(tag1)]
+struct NotATest;
+
+#[tag(tag1)]
+mod not_inline;
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![deny(deprecated)]
+
+use pinny::tag;
+
+#[tag(tag2, tag4)]
+fn tagged_function() {}
+
+fn main() {
+    tagged_function();
+}
@@ -0,0 +1,7 @@
+use pinny::tag;
+
+#[tag(tag3, tag4)]
+#[test]
+fn test_compilation_failure() {}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use pinny::tag;
+
+#[tag(tag1, tag2, tag4)]
+#[test]
+fn test_compilation_failure() {}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use pinny::tag;
+
+#[tag(inherited(not_existent), tag1)]
+#[test]
+fn test_compilation_failure() {}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use pinny::tag;
+
+#[tag(tag1)]
+mod tests {
+    #[rstest]
+    #[case(1)]
+    fn test_rstest(#[case] value: u32) {}
+
+    #[tag(tag2)]
+    #[rstest]
+    fn test_rstest_tagged() {}
+}
+
+fn main() {}
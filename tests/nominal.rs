@@ -37,3 +37,92 @@ fn test_refer_to_tagged_test_original_name() {
 fn test_ignored_tagged_test() {
     panic!();
 }
+
+#[tag(tag1)]
+mod tagged_module {
+    use pinny::tag;
+
+    #[test]
+    fn test_in_tagged_module() {
+        assert_eq!(
+            "tagged_module::test_in_tagged_module::t::tag1::t",
+            function_path!()
+        );
+    }
+
+    #[tag(tag2)]
+    #[test]
+    fn test_with_merged_tags() {
+        assert_eq!(
+            "tagged_module::test_with_merged_tags::t::tag1::tag2::t",
+            function_path!()
+        );
+    }
+
+    #[tag(tag2, tag1)]
+    #[test]
+    fn test_with_duplicated_tags() {
+        assert_eq!(
+            "tagged_module::test_with_duplicated_tags::t::tag1::tag2::t",
+            function_path!()
+        );
+    }
+
+    #[tag(tag2)]
+    mod nested_module {
+        #[test]
+        fn test_in_nested_module() {
+            assert_eq!(
+                "tagged_module::nested_module::test_in_nested_module::t::tag1::tag2::t",
+                function_path!()
+            );
+        }
+    }
+
+    const fn not_a_test() -> bool {
+        true
+    }
+
+    #[test]
+    fn test_invoke_untagged_fn() {
+        assert!(not_a_test());
+    }
+}
+
+#[tag(tag3)]
+#[test]
+fn test_with_implied_tag() {
+    assert_eq!("test_with_implied_tag::t::tag3::tag2::t", function_path!());
+}
+
+#[tag(third, tag1)]
+#[test]
+fn test_with_alias_tag() {
+    assert_eq!(
+        "test_with_alias_tag::t::tag3::tag1::tag2::t",
+        function_path!()
+    );
+}
+
+#[tag(tag5)]
+#[test]
+fn test_ignored_by_tag() {
+    panic!("should be ignored by default");
+}
+
+#[tag(tag5)]
+#[test]
+#[ignore = "explicitly ignored"]
+fn test_explicitly_ignored() {
+    panic!("should be ignored");
+}
+
+#[tag(third, tag1)]
+#[test]
+fn test_current_tags() {
+    use pinny::current_tags;
+
+    assert_eq!(&["tag3", "tag1", "tag2"], current_tags!());
+    let in_closure = || current_tags!().contains(&"tag1");
+    assert!(in_closure());
+}
@@ -0,0 +1,46 @@
+use pinny::tag;
+use pinny_runtime::{entries, TestEntry};
+
+#[tag(tag1)]
+#[test]
+fn test_registered() {}
+
+#[tag(tag1)]
+mod tagged_module {
+    use pinny::tag;
+
+    #[tag(third)]
+    #[test]
+    fn test_registered_in_module() {}
+}
+
+#[tag(tag1)]
+#[allow(dead_code)]
+fn not_a_test() {}
+
+fn find(name: &str) -> &'static TestEntry {
+    entries()
+        .iter()
+        .find(|entry| entry.name() == name)
+        .unwrap_or_else(|| panic!("missing entry for {name}"))
+}
+
+#[test]
+fn test_registry_entries() {
+    let entry = find("test_registered");
+    assert_eq!(&["tag1"], entry.tags());
+    assert_eq!("registry", entry.module_path());
+    assert_eq!("test_registered::t::tag1::t", entry.test_path());
+
+    let entry = find("test_registered_in_module");
+    assert_eq!(&["tag1", "tag3", "tag2"], entry.tags());
+    assert!(entry.has_tag("tag3"));
+    assert!(!entry.has_tag("third"));
+    assert_eq!("registry::tagged_module", entry.module_path());
+    assert_eq!(
+        "tagged_module::test_registered_in_module::t::tag1::tag3::tag2::t",
+        entry.test_path()
+    );
+
+    assert_eq!(2, entries().len());
+}
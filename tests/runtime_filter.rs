@@ -0,0 +1,115 @@
+use pinny::tag;
+use std::process::{Command, ExitCode};
+
+#[tag(tag1)]
+#[test]
+fn test_tag1() {}
+
+#[tag(tag1, tag2)]
+#[test]
+fn test_tag1_tag2() -> Result<(), String> {
+    Ok(())
+}
+
+#[tag(third)]
+#[test]
+fn test_tag3() -> ExitCode {
+    ExitCode::SUCCESS
+}
+
+#[tag(tag2)]
+#[test]
+#[should_panic(expected = "always run")]
+fn test_should_panic() {
+    panic!("always run");
+}
+
+#[tag(tag2)]
+fn tagged_helper() -> u32 {
+    42
+}
+
+#[test]
+fn test_tagged_helper() {
+    assert_eq!(42, tagged_helper());
+}
+
+/// Outcome of a test run by [`run`]
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Skipped,
+    Failed,
+}
+
+/// Run a test of this binary in a child process, with the given filters.
+///
+/// The filters are environment variables, hence a child process per run.
+fn run(test: &str, filters: &[(&str, &str)]) -> Outcome {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env_remove("PINNY_INCLUDE")
+        .env_remove("PINNY_EXCLUDE")
+        .envs(filters.iter().copied())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("running 1 test"), "{test} not found");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        Outcome::Failed
+    } else if stderr.contains("pinny: skipped") {
+        Outcome::Skipped
+    } else {
+        Outcome::Passed
+    }
+}
+
+#[test]
+fn test_runtime_filter() {
+    use Outcome::{Failed, Passed, Skipped};
+
+    let tag1 = "test_tag1::t::tag1::t";
+    let tag1_tag2 = "test_tag1_tag2::t::tag1::tag2::t";
+    assert_eq!(Passed, run(tag1, &[]));
+    assert_eq!(Passed, run(tag1_tag2, &[]));
+
+    let exclude = [("PINNY_EXCLUDE", "tag2")];
+    assert_eq!(Passed, run(tag1, &exclude));
+    assert_eq!(Skipped, run(tag1_tag2, &exclude));
+
+    let exclude = [("PINNY_EXCLUDE", "tag1 and not tag2")];
+    assert_eq!(Skipped, run(tag1, &exclude));
+    assert_eq!(Passed, run(tag1_tag2, &exclude));
+
+    let include = [("PINNY_INCLUDE", "(tag2 or tag3) and not tag1")];
+    assert_eq!(Skipped, run(tag1, &include));
+    assert_eq!(Skipped, run(tag1_tag2, &include));
+
+    let include = [("PINNY_INCLUDE", "tag1 and")];
+    assert_eq!(Failed, run(tag1, &include));
+    let include = [("PINNY_INCLUDE", "Tag1")];
+    assert_eq!(Failed, run(tag1, &include));
+}
+
+#[test]
+fn test_runtime_filter_with_aliases() {
+    use Outcome::{Passed, Skipped};
+
+    let tag3 = "test_tag3::t::tag3::tag2::t";
+    assert_eq!(Passed, run(tag3, &[("PINNY_INCLUDE", "third")]));
+    assert_eq!(Skipped, run(tag3, &[("PINNY_EXCLUDE", "third")]));
+    assert_eq!(Skipped, run(tag3, &[("PINNY_EXCLUDE", "tag3")]));
+    assert_eq!(Passed, run(tag3, &[("PINNY_EXCLUDE", "tag1")]));
+}
+
+#[test]
+fn test_runtime_filter_never_skips() {
+    let exclude = [("PINNY_EXCLUDE", "tag2")];
+    assert_eq!(
+        Outcome::Passed,
+        run("test_should_panic::t::tag2::t", &exclude)
+    );
+    assert_eq!(Outcome::Passed, run("test_tagged_helper", &exclude));
+}
@@ -15,3 +15,15 @@ mod thirdparty_tokio {
         );
     }
 }
+
+mod thirdparty_tokio_current_tags {
+
+    use pinny::{current_tags, tag};
+    use tokio::test;
+
+    #[tag(tag2)]
+    #[test]
+    async fn test_tokio_current_tags() {
+        assert_eq!(&["tag2"], current_tags!());
+    }
+}